@@ -0,0 +1,59 @@
+//! A standalone example, not a puzzle day: runs the same Game of Life on a `HashSet` of positions and on a `BitGrid`.
+//!
+//! Pass `--time` to bench both implementations:
+//! `cargo run --release --example bit_grid -- --time`
+use std::collections::{HashMap, HashSet};
+
+use advent_of_code::grid::{BitGrid, Grid, DIRECTIONS_8};
+use advent_of_code::template::runner::run_labelled;
+use advent_of_code::template::testing::Rng;
+
+const SIZE: usize = 256;
+const STEPS: usize = 100;
+
+fn hash_set(grid: &Grid) -> Option<usize> {
+    let mut alive: HashSet<(i64, i64)> =
+        grid.find('#').into_iter().map(|(x, y)| (x as i64, y as i64)).collect();
+
+    for _ in 0..STEPS {
+        let mut counts: HashMap<(i64, i64), u8> = HashMap::new();
+        for (x, y) in &alive {
+            for (dx, dy) in DIRECTIONS_8 {
+                let n = (x + dx, y + dy);
+                if n.0 >= 0 && n.1 >= 0 && n.0 < SIZE as i64 && n.1 < SIZE as i64 {
+                    *counts.entry(n).or_default() += 1;
+                }
+            }
+        }
+        alive = counts
+            .into_iter()
+            .filter(|(p, count)| *count == 3 || (*count == 2 && alive.contains(p)))
+            .map(|(p, _)| p)
+            .collect();
+    }
+
+    Some(alive.len())
+}
+
+fn bit_grid(grid: &Grid) -> Option<usize> {
+    let mut alive = BitGrid::from_grid(grid, |c| *c == '#');
+
+    for _ in 0..STEPS {
+        let counts = alive.neighbour_counts(&DIRECTIONS_8);
+        alive &= &counts.exactly(2);
+        alive |= &counts.exactly(3);
+    }
+
+    Some(alive.count_ones())
+}
+
+fn main() {
+    let mut rng = Rng::new(2024);
+    let rows = (0..SIZE)
+        .map(|_| (0..SIZE).map(|_| if rng.chance(0.3) { '#' } else { '.' }).collect())
+        .collect();
+    let grid = Grid::from_rows(rows).unwrap();
+
+    run_labelled(hash_set, &grid, "HashSet");
+    run_labelled(bit_grid, &grid, "BitGrid");
+}
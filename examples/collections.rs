@@ -0,0 +1,93 @@
+//! A standalone example, not a puzzle day: small workloads for components with a `DisjointSet`, tallies with a
+//! `Counter`, Dijkstra with a `MinHeap` and memoized Collatz chains with an `LruCache`.
+//!
+//! Pass `--time` to bench each workload:
+//! `cargo run --release --example collections -- --time`
+use advent_of_code::collections::{Counter, DisjointSet, LruCache, MinHeap};
+use advent_of_code::grid::{Grid, GridLike, DIRECTIONS_4};
+use advent_of_code::template::runner::run_labelled;
+use advent_of_code::template::testing::Rng;
+
+const NODES: usize = 100_000;
+const SIZE: usize = 200;
+
+struct Input {
+    edges: Vec<(usize, usize)>,
+    numbers: Vec<u32>,
+    risks: Grid<u32>,
+}
+
+fn components(input: &Input) -> Option<usize> {
+    let mut set = DisjointSet::new(NODES);
+    for &(a, b) in &input.edges {
+        set.union(a, b);
+    }
+    Some(set.count())
+}
+
+fn most_common(input: &Input) -> Option<usize> {
+    let counter: Counter<u32> = input.numbers.iter().copied().collect();
+    counter.most_common().first().map(|(_, count)| *count)
+}
+
+fn lowest_risk(input: &Input) -> Option<u32> {
+    let target = ((SIZE - 1) as i64, (SIZE - 1) as i64);
+    let mut best = vec![u32::MAX; SIZE * SIZE];
+    let mut queue = MinHeap::new();
+    queue.push((0, (0i64, 0i64)));
+    while let Some((risk, (x, y))) = queue.pop() {
+        if (x, y) == target {
+            return Some(risk);
+        }
+        for (dx, dy) in DIRECTIONS_4 {
+            let (nx, ny) = (x + dx, y + dy);
+            if let Some(cell) = input.risks.cell((nx, ny)) {
+                let next = risk + cell;
+                let i = ny as usize * SIZE + nx as usize;
+                if next < best[i] {
+                    best[i] = next;
+                    queue.push((next, (nx, ny)));
+                }
+            }
+        }
+    }
+    None
+}
+
+fn longest_collatz(_: &Input) -> Option<u64> {
+    let mut lengths = LruCache::new(4096);
+    let mut longest = (0, 0);
+    for start in 1..100_000u64 {
+        let mut path = Vec::new();
+        let mut n = start;
+        let mut length = loop {
+            if n == 1 {
+                break 1;
+            }
+            if let Some(length) = lengths.get(&n) {
+                break *length;
+            }
+            path.push(n);
+            n = if n.is_multiple_of(2) { n / 2 } else { 3 * n + 1 };
+        };
+        for n in path.into_iter().rev() {
+            length += 1;
+            lengths.insert(n, length);
+        }
+        longest = longest.max((length, start));
+    }
+    Some(longest.1)
+}
+
+fn main() {
+    let mut rng = Rng::new(2024);
+    let edges = (0..NODES * 4 / 5).map(|_| (rng.below(NODES), rng.below(NODES))).collect();
+    let numbers = (0..1_000_000).map(|_| rng.below(1000) as u32).collect();
+    let rows = (0..SIZE).map(|_| (0..SIZE).map(|_| rng.range(1..=9) as u32).collect()).collect();
+    let input = Input { edges, numbers, risks: Grid::from_rows(rows).unwrap() };
+
+    run_labelled(components, &input, "DisjointSet");
+    run_labelled(most_common, &input, "Counter");
+    run_labelled(lowest_risk, &input, "MinHeap");
+    run_labelled(longest_collatz, &input, "LruCache");
+}
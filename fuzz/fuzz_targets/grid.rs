@@ -0,0 +1,29 @@
+#![no_main]
+
+use advent_of_code::grid::Grid;
+use libfuzzer_sys::fuzz_target;
+
+const DIRECTIONS: [(i32, i32); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+fuzz_target!(|input: &str| {
+    let Ok(grid) = Grid::new(input) else {
+        return;
+    };
+
+    let (width, height) = (grid.width() as i32, grid.height() as i32);
+
+    // positions just outside of the grid must be rejected, not panic.
+    for (x, y) in [(-1, 0), (0, -1), (width, 0), (0, height), (width - 1, height - 1)] {
+        let _ = grid.is_valid(x, y);
+    }
+
+    for c in input.chars().take(4) {
+        for (x, y) in grid.find(c) {
+            assert_eq!(grid.get(x, y), c);
+            for direction in DIRECTIONS {
+                let path = grid.path((x as i32, y as i32), 4, direction);
+                assert_eq!(path.first(), Some(&c));
+            }
+        }
+    }
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+use advent_of_code::vm::Memory;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let Ok(mut memory) = Memory::parse(input) else {
+        return;
+    };
+
+    // printing and parsing the words again must give the same memory.
+    let printed: Vec<String> = memory.words().iter().map(|word| word.to_string()).collect();
+    assert_eq!(Memory::parse(&printed.join(",")).as_ref(), Ok(&memory));
+
+    // the words double as addresses: negative ones are rejected, all others can be written and read back.
+    let addresses: Vec<_> = memory.words().iter().copied().take(8).collect();
+    for address in addresses {
+        match memory.set(address, 7) {
+            Ok(()) => assert_eq!(memory.get(address).ok(), Some(7)),
+            Err(_) => assert!(address < 0 && memory.get(address).is_err()),
+        }
+    }
+});
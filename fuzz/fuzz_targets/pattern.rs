@@ -0,0 +1,25 @@
+#![no_main]
+
+use advent_of_code::text::Pattern;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    // the first line is the pattern, the rest is the text it is matched against.
+    let (pattern, text) = input.split_once('\n').unwrap_or((input, ""));
+    let Ok(pattern) = Pattern::new(pattern) else {
+        return;
+    };
+
+    if let Some(values) = pattern.matches(text) {
+        let captures = pattern.match_prefix(text).unwrap();
+        assert_eq!(captures.end, text.len());
+        assert_eq!(captures.values, values);
+    }
+
+    let mut previous_end = 0;
+    for captures in pattern.find_iter(text) {
+        assert!(previous_end <= captures.start && captures.start < captures.end);
+        assert_eq!(pattern.match_prefix(&text[captures.start..]).map(|c| c.values), Some(captures.values));
+        previous_end = captures.end;
+    }
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use advent_of_code::printer::Printer;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let Ok(printer) = Printer::new(input) else {
+        return;
+    };
+
+    let valid = printer.get_valid_jobs().len();
+    let invalid = printer.get_invalid_jobs().len();
+    assert_eq!(valid + invalid, printer.get_jobs().len());
+});
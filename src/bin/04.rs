@@ -23,7 +23,7 @@ const DIRECTIONS: [(i32, i32); 8] = [
 ];
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let grid = Grid::new(input);
+    let grid = Grid::new(input).ok()?;
     let positions = grid.find('X');
     let mut count = 0;
 
@@ -50,7 +50,7 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let grid = Grid::new(input);
+    let grid = Grid::new(input).ok()?;
     let positions: Vec<(i32, i32)> = grid
         .find('A')
         .iter()
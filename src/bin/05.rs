@@ -3,7 +3,7 @@ use advent_of_code::{ printer::Printer, take_middle_value };
 advent_of_code::solution!(5);
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let printer = Printer::new(input);
+    let printer = Printer::new(input).ok()?;
     let result: u32 = printer
         .get_valid_jobs()
         .iter()
@@ -14,7 +14,7 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let printer = Printer::new(input);
+    let printer = Printer::new(input).ok()?;
     let result = printer
         .get_invalid_jobs()
         .iter()
@@ -0,0 +1,130 @@
+use std::collections::hash_map::{self, HashMap};
+use std::hash::Hash;
+use std::ops::Index;
+
+/** a multiset that counts how often each item was added */
+#[derive(Clone, Debug)]
+pub struct Counter<T> {
+    counts: HashMap<T, usize>,
+    total: usize,
+}
+
+impl<T> Default for Counter<T> {
+    fn default() -> Self {
+        Self { counts: HashMap::new(), total: 0 }
+    }
+}
+
+impl<T: Hash + Eq> Counter<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    pub fn add_n(&mut self, item: T, n: usize) {
+        if n > 0 {
+            *self.counts.entry(item).or_insert(0) += n;
+            self.total += n;
+        }
+    }
+
+    /** removes one occurrence of the item; false if there was none */
+    pub fn remove(&mut self, item: &T) -> bool {
+        let Some(count) = self.counts.get_mut(item) else {
+            return false;
+        };
+        *count -= 1;
+        if *count == 0 {
+            self.counts.remove(item);
+        }
+        self.total -= 1;
+        true
+    }
+
+    /** how often the item was added, 0 if never */
+    pub fn get(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /** the number of distinct items */
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /** the number of items, counting repeats */
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /** every distinct item with its count, in arbitrary order */
+    pub fn iter(&self) -> hash_map::Iter<'_, T, usize> {
+        self.counts.iter()
+    }
+
+    /** every distinct item with its count, most common first */
+    pub fn most_common(&self) -> Vec<(&T, usize)> {
+        let mut items: Vec<(&T, usize)> = self.counts.iter().map(|(item, count)| (item, *count)).collect();
+        items.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        items
+    }
+}
+
+impl<T: Hash + Eq> Index<&T> for Counter<T> {
+    type Output = usize;
+
+    fn index(&self, item: &T) -> &usize {
+        self.counts.get(item).unwrap_or(&0)
+    }
+}
+
+impl<T: Hash + Eq> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Counter<T> {
+    type Item = (&'a T, &'a usize);
+    type IntoIter = hash_map::Iter<'a, T, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter() {
+        // the right list of the day 1 example
+        let mut counter: Counter<u32> = [4, 3, 5, 3, 9, 3].into_iter().collect();
+        assert_eq!((counter[&3], counter[&7], counter.get(&4)), (3, 0, 1));
+        assert_eq!((counter.len(), counter.total()), (4, 6));
+        assert_eq!(counter.most_common()[0], (&3, 3));
+
+        assert!(counter.remove(&4));
+        assert!(!counter.remove(&4));
+        counter.add_n(9, 2);
+        assert_eq!((counter.len(), counter.total(), counter[&9]), (3, 7, 3));
+        assert_eq!(counter.iter().map(|(_, count)| count).sum::<usize>(), counter.total());
+    }
+}
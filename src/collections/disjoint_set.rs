@@ -0,0 +1,119 @@
+/** disjoint-set union over the elements `0..n`, with path compression and union by rank */
+#[derive(Clone, Debug)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    count: usize,
+}
+
+impl DisjointSet {
+    /** `n` sets with one element each */
+    pub fn new(n: usize) -> Self {
+        Self { parents: (0..n).collect(), ranks: vec![0; n], sizes: vec![1; n], count: n }
+    }
+
+    /** the number of elements */
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /** the number of disjoint sets */
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /** the representative of the set that contains `x` */
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        // point everything on the way directly at the root
+        let mut x = x;
+        while self.parents[x] != root {
+            x = std::mem::replace(&mut self.parents[x], root);
+        }
+        root
+    }
+
+    /** merges the sets of `a` and `b`; false if they already were the same set */
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (root, child) = if self.ranks[a] < self.ranks[b] { (b, a) } else { (a, b) };
+        if self.ranks[root] == self.ranks[child] {
+            self.ranks[root] += 1;
+        }
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        self.count -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /** the size of the set that contains `x` */
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    /** the elements of every set, ordered by their smallest element */
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut indices = vec![usize::MAX; self.len()];
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for x in 0..self.len() {
+            let root = self.find(x);
+            if indices[root] == usize::MAX {
+                indices[root] = groups.len();
+                groups.push(Vec::new());
+            }
+            groups[indices[root]].push(x);
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::testing::Rng;
+
+    #[test]
+    fn test_disjoint_set() {
+        let mut set = DisjointSet::new(6);
+        assert!(set.union(0, 1));
+        assert!(set.union(4, 1));
+        assert!(!set.union(0, 4));
+        assert!(set.union(2, 3));
+        assert!(set.same(4, 0) && !set.same(4, 3));
+        assert_eq!((set.count(), set.size(1)), (3, 3));
+        assert_eq!(set.groups(), vec![vec![0, 1, 4], vec![2, 3], vec![5]]);
+    }
+
+    #[test]
+    fn test_disjoint_set_against_labels() {
+        let mut rng = Rng::new(46);
+        let mut set = DisjointSet::new(200);
+        let mut labels: Vec<usize> = (0..200).collect();
+        for _ in 0..150 {
+            let (a, b) = (rng.below(200), rng.below(200));
+            let (from, to) = (labels[a], labels[b]);
+            labels.iter_mut().filter(|label| **label == from).for_each(|label| *label = to);
+            assert_eq!(set.union(a, b), from != to);
+
+            let (c, d) = (rng.below(200), rng.below(200));
+            assert_eq!(set.same(c, d), labels[c] == labels[d]);
+            assert_eq!(set.size(c), labels.iter().filter(|label| **label == labels[c]).count());
+        }
+    }
+}
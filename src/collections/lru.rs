@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+const NONE: usize = usize::MAX;
+
+#[derive(Clone, Debug)]
+struct Entry<K, V> {
+    key: K,
+    value: V,
+    // neighbours in the recency list, `NONE` at either end
+    newer: usize,
+    older: usize,
+}
+
+/** a memo table with a fixed number of entries that forgets the least recently used entry when full */
+#[derive(Clone, Debug)]
+pub struct LruCache<K, V> {
+    capacity: usize,
+    indices: HashMap<K, usize>,
+    entries: Vec<Entry<K, V>>,
+    newest: usize,
+    oldest: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    /** panics if the capacity is zero */
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must not be zero");
+        Self {
+            capacity,
+            indices: HashMap::with_capacity(capacity),
+            entries: Vec::with_capacity(capacity),
+            newest: NONE,
+            oldest: NONE,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    /** the value of a key, marking it as the most recently used */
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let i = *self.indices.get(key)?;
+        self.touch(i);
+        Some(&self.entries[i].value)
+    }
+
+    /** the value of a key, without changing which entry is forgotten next */
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.indices.get(key).map(|&i| &self.entries[i].value)
+    }
+
+    /** stores a value, replacing the old value of the key or forgetting the least recently used entry when full */
+    pub fn insert(&mut self, key: K, value: V) {
+        if let Some(&i) = self.indices.get(&key) {
+            self.entries[i].value = value;
+            self.touch(i);
+            return;
+        }
+
+        let i = if self.entries.len() < self.capacity {
+            self.entries.push(Entry { key: key.clone(), value, newer: NONE, older: NONE });
+            self.entries.len() - 1
+        } else {
+            // reuse the slot of the oldest entry
+            let i = self.oldest;
+            self.unlink(i);
+            let entry = &mut self.entries[i];
+            self.indices.remove(&entry.key);
+            entry.key = key.clone();
+            entry.value = value;
+            i
+        };
+        self.indices.insert(key, i);
+        self.push_newest(i);
+    }
+
+    /** the value of a key, computing and storing it first if it is missing */
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce() -> V) -> &V {
+        let i = match self.indices.get(&key) {
+            Some(&i) => {
+                self.touch(i);
+                i
+            }
+            None => {
+                self.insert(key, f());
+                self.newest
+            }
+        };
+        &self.entries[i].value
+    }
+
+    pub fn clear(&mut self) {
+        self.indices.clear();
+        self.entries.clear();
+        self.newest = NONE;
+        self.oldest = NONE;
+    }
+
+    fn touch(&mut self, i: usize) {
+        if self.newest != i {
+            self.unlink(i);
+            self.push_newest(i);
+        }
+    }
+
+    fn unlink(&mut self, i: usize) {
+        let Entry { newer, older, .. } = self.entries[i];
+        match newer {
+            NONE => self.newest = older,
+            newer => self.entries[newer].older = older,
+        }
+        match older {
+            NONE => self.oldest = newer,
+            older => self.entries[older].newer = newer,
+        }
+    }
+
+    fn push_newest(&mut self, i: usize) {
+        self.entries[i].newer = NONE;
+        self.entries[i].older = self.newest;
+        match self.newest {
+            NONE => self.oldest = i,
+            newest => self.entries[newest].newer = i,
+        }
+        self.newest = i;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::testing::Rng;
+
+    #[test]
+    fn test_lru_cache() {
+        let mut cache = LruCache::new(2);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        assert_eq!(cache.get(&"a"), Some(&1));
+        cache.insert("c", 3);
+        assert!(!cache.contains(&"b"));
+        assert_eq!(*cache.get_or_insert_with("a", || unreachable!()), 1);
+        assert_eq!(*cache.get_or_insert_with("d", || 4), 4);
+        assert_eq!((cache.peek(&"a"), cache.peek(&"c"), cache.len()), (Some(&1), None, 2));
+    }
+
+    #[test]
+    fn test_lru_cache_against_list() {
+        let mut rng = Rng::new(46);
+        let mut cache = LruCache::new(5);
+        // most recently used last
+        let mut expected: Vec<(usize, u64)> = Vec::new();
+        for _ in 0..1000 {
+            let (key, value) = (rng.below(10), rng.next_u64());
+            let position = expected.iter().position(|(k, _)| *k == key);
+            if rng.bool() {
+                assert_eq!(cache.get(&key), position.map(|i| &expected[i].1));
+                if let Some(i) = position {
+                    let entry = expected.remove(i);
+                    expected.push(entry);
+                }
+            } else {
+                cache.insert(key, value);
+                match position {
+                    Some(i) => _ = expected.remove(i),
+                    None if expected.len() == 5 => _ = expected.remove(0),
+                    None => {}
+                }
+                expected.push((key, value));
+            }
+            assert_eq!(cache.len(), expected.len());
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/** a priority queue that pops the smallest item first, e.g. `(cost, state)` pairs for Dijkstra */
+#[derive(Clone, Debug)]
+pub struct MinHeap<T> {
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> Default for MinHeap<T> {
+    fn default() -> Self {
+        Self { heap: BinaryHeap::new() }
+    }
+}
+
+impl<T: Ord> MinHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.heap.push(Reverse(item));
+    }
+
+    /** removes and returns the smallest item */
+    pub fn pop(&mut self) -> Option<T> {
+        self.heap.pop().map(|Reverse(item)| item)
+    }
+
+    /** the smallest item */
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek().map(|Reverse(item)| item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+}
+
+impl<T: Ord> Extend<T> for MinHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.heap.extend(iter.into_iter().map(Reverse));
+    }
+}
+
+impl<T: Ord> FromIterator<T> for MinHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self { heap: iter.into_iter().map(Reverse).collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::testing::Rng;
+
+    #[test]
+    fn test_min_heap() {
+        let mut rng = Rng::new(46);
+        let mut items: Vec<i64> = (0..100).map(|_| rng.range(-50..=50)).collect();
+        let mut heap: MinHeap<i64> = items[..50].iter().copied().collect();
+        heap.extend(items[50..].iter().copied());
+        items.sort();
+
+        assert_eq!(heap.peek(), items.first());
+        assert_eq!(std::iter::from_fn(|| heap.pop()).collect::<Vec<_>>(), items);
+        assert!(heap.is_empty());
+    }
+}
@@ -0,0 +1,9 @@
+pub use counter::Counter;
+pub use disjoint_set::DisjointSet;
+pub use lru::LruCache;
+pub use min_heap::MinHeap;
+
+mod counter;
+mod disjoint_set;
+mod lru;
+mod min_heap;
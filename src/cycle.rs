@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/** the states of a sequence up to the end of its first cycle; step `start + length` is step `start` again */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle<S> {
+    pub start: usize,
+    pub length: usize,
+    states: Vec<S>,
+}
+
+impl<S> Cycle<S> {
+    /** the step within the first cycle that has the same state as step `n` */
+    pub fn index_at(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    /** the state after `n` steps */
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.states[self.index_at(n)]
+    }
+
+    /** the states before the cycle, followed by one pass through it */
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+}
+
+/** runs `step` from `initial` until a state repeats, remembering every state in a hash map */
+pub fn find_cycle<S: Clone + Hash + Eq>(initial: S, step: impl FnMut(&S) -> S) -> Cycle<S> {
+    find_cycle_by_key(initial, step, S::clone)
+}
+
+/** like `find_cycle`, but only compares the keys of states, e.g. to ignore a step counter or to store a smaller hash */
+pub fn find_cycle_by_key<S, K: Hash + Eq>(initial: S, mut step: impl FnMut(&S) -> S, mut key: impl FnMut(&S) -> K) -> Cycle<S> {
+    let mut seen = HashMap::new();
+    let mut states = vec![initial];
+    loop {
+        let i = states.len() - 1;
+        if let Some(start) = seen.insert(key(&states[i]), i) {
+            states.pop();
+            return Cycle { start, length: i - start, states };
+        }
+        let next = step(&states[i]);
+        states.push(next);
+    }
+}
+
+/** the state after `n` steps, skipping ahead once a state repeats */
+pub fn fast_forward<S: Clone + Hash + Eq>(initial: S, n: usize, step: impl FnMut(&S) -> S) -> S {
+    fast_forward_by_key(initial, n, step, S::clone)
+}
+
+/** like `fast_forward`, but only compares the keys of states */
+pub fn fast_forward_by_key<S, K: Hash + Eq>(initial: S, n: usize, mut step: impl FnMut(&S) -> S, mut key: impl FnMut(&S) -> K) -> S {
+    let mut seen = HashMap::new();
+    let mut states = vec![initial];
+    loop {
+        let i = states.len() - 1;
+        if i == n {
+            return states.swap_remove(i);
+        }
+        if let Some(start) = seen.insert(key(&states[i]), i) {
+            return states.swap_remove(start + (n - start) % (i - start));
+        }
+        let next = step(&states[i]);
+        states.push(next);
+    }
+}
+
+/** Brent's algorithm: returns `(start, length)` of the cycle while keeping only two states in memory,
+at the cost of running `step` about three times as often as `find_cycle` */
+pub fn brent<S: Clone + Eq>(initial: &S, mut step: impl FnMut(&S) -> S) -> (usize, usize) {
+    let (mut power, mut length) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    (start, length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    fn next(x: &u32) -> u32 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let cycle = find_cycle(3, next);
+        assert_eq!(brent(&3, next), (cycle.start, cycle.length));
+        assert_eq!(cycle.states().len(), cycle.start + cycle.length);
+
+        let mut x = 3;
+        for n in 0..100 {
+            assert_eq!(*cycle.state_at(n), x);
+            assert_eq!(fast_forward(3, n, next), x);
+            x = next(&x);
+        }
+    }
+
+    #[test]
+    fn test_find_cycle_by_key() {
+        // the counter differs in every state, so only the key can repeat
+        let cycle = find_cycle_by_key((0u64, 1u32), |(i, x)| (i + 1, x * 2 % 12), |(_, x)| *x);
+        assert_eq!((cycle.start, cycle.length), (2, 2));
+        assert_eq!(fast_forward_by_key((0u64, 1u32), 1_000_000_001, |(i, x)| (i + 1, x * 2 % 12), |(_, x)| *x), (3, 8));
+    }
+
+    #[test]
+    fn test_fast_forward_grid() {
+        let grid = Grid::new("ab\ncd").unwrap();
+        let rotate = |grid: &Grid| grid.view().rotate().to_grid();
+        assert_eq!(fast_forward(grid.clone(), 1_000_000_001, rotate), rotate(&grid));
+    }
+}
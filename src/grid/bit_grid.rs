@@ -0,0 +1,292 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use super::{Grid, GridLike, Point};
+
+const BITS: usize = u64::BITS as usize;
+
+/** a grid of booleans that stores one bit per cell, so whole rows can be combined 64 cells at a time */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    // words per row; bits past the width are always zero
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(BITS);
+        Self { width, height, stride, words: vec![0; stride * height] }
+    }
+
+    /** sets the cells for which the predicate holds */
+    pub fn from_grid<T>(grid: &Grid<T>, mut predicate: impl FnMut(&T) -> bool) -> Self {
+        let mut bits = Self::new(grid.width(), grid.height());
+        for (y, row) in grid.rows().iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if predicate(cell) {
+                    bits.set(x, y, true);
+                }
+            }
+        }
+        bits
+    }
+
+    /** a grid of characters, with `set` for set cells and `unset` for the others */
+    pub fn to_grid(&self, set: char, unset: char) -> Grid {
+        let grid = (0..self.height)
+            .map(|y| (0..self.width).map(|x| if self.get(x, y) { set } else { unset }).collect())
+            .collect();
+        Grid { grid, width: self.width }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        assert!(x < self.width && y < self.height, "({x}, {y}) is outside of the grid");
+        self.words[y * self.stride + x / BITS] >> (x % BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        assert!(x < self.width && y < self.height, "({x}, {y}) is outside of the grid");
+        let word = &mut self.words[y * self.stride + x / BITS];
+        if value {
+            *word |= 1 << (x % BITS);
+        } else {
+            *word &= !(1 << (x % BITS));
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|word| *word != 0)
+    }
+
+    /** the positions of set cells, row by row */
+    pub fn ones(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let (y, x0) = (i / self.stride, i % self.stride * BITS);
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    (x0 + bit, y)
+                })
+            })
+        })
+    }
+
+    /** cells set in `self` but not in `other` */
+    pub fn difference(&self, other: &BitGrid) -> BitGrid {
+        self.zip(other, |a, b| a & !b)
+    }
+
+    /** moves every cell by `(dx, dy)`; cells moved outside of the grid are dropped */
+    pub fn shift(&self, dx: isize, dy: isize) -> BitGrid {
+        let mut shifted = Self::new(self.width, self.height);
+        for y in 0..self.height {
+            let Some(source_y) = y.checked_add_signed(-dy).filter(|y| *y < self.height) else {
+                continue;
+            };
+            let source = &self.words[source_y * self.stride..][..self.stride];
+            let target = &mut shifted.words[y * self.stride..][..self.stride];
+            shift_row(source, target, dx);
+        }
+        shifted.clear_padding();
+        shifted
+    }
+
+    /** counts the set neighbours of every cell in the given directions, e.g. `DIRECTIONS_8` */
+    pub fn neighbour_counts(&self, directions: &[Point]) -> NeighbourCounts {
+        let depth = (usize::BITS - directions.len().leading_zeros()).max(1) as usize;
+        let mut planes = vec![Self::new(self.width, self.height); depth];
+
+        // adds one bit to the count of every cell at once, carrying into the next plane like a binary adder
+        for &(dx, dy) in directions {
+            let mut carry = self.shift(-dx as isize, -dy as isize);
+            for plane in &mut planes {
+                let next = &*plane & &carry;
+                *plane ^= &carry;
+                carry = next;
+            }
+        }
+        NeighbourCounts { planes }
+    }
+
+    fn zip(&self, other: &BitGrid, f: impl Fn(u64, u64) -> u64) -> BitGrid {
+        assert_eq!((self.width, self.height), (other.width, other.height), "grids must have the same size");
+        let words = self.words.iter().zip(&other.words).map(|(a, b)| f(*a, *b)).collect();
+        BitGrid { words, ..*self }
+    }
+
+    fn clear_padding(&mut self) {
+        if !self.width.is_multiple_of(BITS) {
+            let mask = (1 << (self.width % BITS)) - 1;
+            for row in self.words.chunks_mut(self.stride) {
+                row[self.stride - 1] &= mask;
+            }
+        }
+    }
+}
+
+/** shifts the bits of a row towards higher x for positive `dx` */
+fn shift_row(source: &[u64], target: &mut [u64], dx: isize) {
+    let (words, bits) = (dx.unsigned_abs() / BITS, dx.unsigned_abs() % BITS);
+    let word = |i: Option<usize>| i.and_then(|i| source.get(i)).copied().unwrap_or(0);
+    for (i, target) in target.iter_mut().enumerate() {
+        *target = if dx >= 0 {
+            let (low, lower) = (word(i.checked_sub(words)), word(i.checked_sub(words + 1)));
+            if bits == 0 { low } else { low << bits | lower >> (BITS - bits) }
+        } else {
+            let (high, higher) = (word(Some(i + words)), word(Some(i + words + 1)));
+            if bits == 0 { high } else { high >> bits | higher << (BITS - bits) }
+        };
+    }
+}
+
+/** neighbour counts of every cell, stored as one bit grid per binary digit */
+#[derive(Clone, Debug)]
+pub struct NeighbourCounts {
+    planes: Vec<BitGrid>,
+}
+
+impl NeighbourCounts {
+    pub fn get(&self, x: usize, y: usize) -> u32 {
+        self.planes.iter().enumerate().map(|(i, plane)| u32::from(plane.get(x, y)) << i).sum()
+    }
+
+    /** the cells with exactly `n` neighbours */
+    pub fn exactly(&self, n: u32) -> BitGrid {
+        let empty = BitGrid::new(self.planes[0].width, self.planes[0].height);
+        if n >> self.planes.len() != 0 {
+            return empty;
+        }
+        let mut result = !&empty;
+        for (i, plane) in self.planes.iter().enumerate() {
+            if n >> i & 1 == 1 {
+                result &= plane;
+            } else {
+                result = result.difference(plane);
+            }
+        }
+        result
+    }
+}
+
+impl GridLike for BitGrid {
+    type Cell = bool;
+
+    fn cell(&self, (x, y): Point) -> Option<&bool> {
+        let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        (x < self.width && y < self.height).then(|| if self.get(x, y) { &true } else { &false })
+    }
+
+    fn bounds(&self) -> Option<(Point, Point)> {
+        (self.width > 0 && self.height > 0).then(|| ((0, 0), (self.width as i64 - 1, self.height as i64 - 1)))
+    }
+}
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut result = BitGrid { words: self.words.iter().map(|word| !word).collect(), ..*self };
+        result.clear_padding();
+        result
+    }
+}
+
+macro_rules! bit_operator {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl $trait for &BitGrid {
+            type Output = BitGrid;
+
+            fn $method(self, other: &BitGrid) -> BitGrid {
+                self.zip(other, |a, b| a $op b)
+            }
+        }
+
+        impl $assign_trait<&BitGrid> for BitGrid {
+            fn $assign_method(&mut self, other: &BitGrid) {
+                *self = &*self $op other;
+            }
+        }
+    };
+}
+
+bit_operator!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+bit_operator!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+bit_operator!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::DIRECTIONS_8;
+    use crate::template::testing::Rng;
+
+    fn random(width: usize, height: usize, seed: u64) -> BitGrid {
+        let mut rng = Rng::new(seed);
+        let mut grid = BitGrid::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                grid.set(x, y, rng.chance(0.4));
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn test_bit_grid_conversion() {
+        let grid = Grid::new("#..#\n.##.").unwrap();
+        let bits = BitGrid::from_grid(&grid, |c| *c == '#');
+        assert_eq!(bits.count_ones(), 4);
+        assert_eq!(bits.ones().collect::<Vec<_>>(), vec![(0, 0), (3, 0), (1, 1), (2, 1)]);
+        assert_eq!(bits.to_grid('#', '.'), grid);
+        assert_eq!((!&bits).to_grid('#', '.'), Grid::new(".##.\n#..#").unwrap());
+    }
+
+    #[test]
+    fn test_bit_grid_shift() {
+        let grid = random(150, 5, 1);
+        for (dx, dy) in [(1, 0), (-1, 1), (64, -2), (-70, 0), (129, 3), (0, 5)] {
+            let shifted = grid.shift(dx, dy);
+            for y in 0..5 {
+                for x in 0..150 {
+                    let source = (x as isize - dx, y as isize - dy);
+                    let expected = source.0 >= 0 && source.0 < 150 && source.1 >= 0 && source.1 < 5
+                        && grid.get(source.0 as usize, source.1 as usize);
+                    assert_eq!(shifted.get(x, y), expected, "({x}, {y}) shifted by ({dx}, {dy})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bit_grid_neighbour_counts() {
+        let grid = random(70, 20, 2);
+        let counts = grid.neighbour_counts(&DIRECTIONS_8);
+        for y in 0..20 {
+            for x in 0..70 {
+                let expected = grid.neighbours((x as i64, y as i64), &DIRECTIONS_8).filter(|(_, c)| **c).count();
+                assert_eq!(counts.get(x, y), expected as u32);
+                assert_eq!(counts.exactly(3).get(x, y), expected == 3);
+            }
+        }
+        assert!(!counts.exactly(16).any());
+
+        let other = random(70, 20, 3);
+        assert_eq!((&grid & &other).count_ones() + (&grid | &other).count_ones(), grid.count_ones() + other.count_ones());
+        assert_eq!(grid.difference(&other), &grid ^ &(&grid & &other));
+    }
+}
@@ -0,0 +1,104 @@
+use super::{Frame, Grid};
+
+/** a position as `(x, y)`, signed so that sparse and infinite grids can extend in every direction */
+pub type Point = (i64, i64);
+
+pub const DIRECTIONS_4: [Point; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+pub const DIRECTIONS_8: [Point; 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+/** shared access to dense, sparse and infinite grids */
+pub trait GridLike {
+    type Cell;
+
+    /** the cell at a position, or None if the position is outside the grid or empty */
+    fn cell(&self, p: Point) -> Option<&Self::Cell>;
+
+    /** the top left and bottom right corners of the smallest rectangle holding every cell, or None if there are no cells */
+    fn bounds(&self) -> Option<(Point, Point)>;
+
+    /** every cell with its position; dense grids yield them row by row */
+    fn cells(&self) -> impl Iterator<Item = (Point, &Self::Cell)> {
+        let ((x0, y0), (x1, y1)) = self.bounds().unwrap_or(((0, 0), (-1, -1)));
+        (y0..=y1)
+            .flat_map(move |y| (x0..=x1).map(move |x| (x, y)))
+            .filter_map(|p| self.cell(p).map(|cell| (p, cell)))
+    }
+
+    /** the cells next to a position in the given directions, e.g. `DIRECTIONS_4` */
+    fn neighbours<'a>(&'a self, p: Point, directions: &'a [Point]) -> impl Iterator<Item = (Point, &'a Self::Cell)> + 'a {
+        directions.iter().filter_map(move |(dx, dy)| {
+            let n = (p.0 + dx, p.1 + dy);
+            self.cell(n).map(|cell| (n, cell))
+        })
+    }
+
+    /** the positions of all cells matching a predicate */
+    fn find_where(&self, mut predicate: impl FnMut(&Self::Cell) -> bool) -> Vec<Point> {
+        self.cells().filter(|(_, cell)| predicate(cell)).map(|(p, _)| p).collect()
+    }
+
+    /** renders a rectangle given by its inclusive corners; the top left corner becomes `(0, 0)` in the frame */
+    fn render_area(&self, min: Point, max: Point, f: impl Fn(Option<&Self::Cell>) -> char) -> Frame {
+        let rows = (min.1..=max.1).map(|y| (min.0..=max.0).map(|x| f(self.cell((x, y)))).collect()).collect();
+        Grid::from_rows(rows).expect("area to render must not be empty").render()
+    }
+
+    /** renders every cell within the bounds, or None if there are no cells */
+    fn render_with(&self, f: impl Fn(Option<&Self::Cell>) -> char) -> Option<Frame> {
+        let (min, max) = self.bounds()?;
+        Some(self.render_area(min, max, f))
+    }
+}
+
+impl<T> GridLike for Grid<T> {
+    type Cell = T;
+
+    fn cell(&self, (x, y): Point) -> Option<&T> {
+        let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        self.grid.get(y)?.get(x)
+    }
+
+    fn bounds(&self) -> Option<(Point, Point)> {
+        Some(((0, 0), (self.width() as i64 - 1, self.height() as i64 - 1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{InfiniteGrid, SparseGrid};
+    use super::*;
+
+    const INPUT: &str = "#..\n.#.\n..#";
+
+    fn neighbours_of(grid: &impl GridLike<Cell = char>, p: Point) -> Vec<char> {
+        grid.neighbours(p, &DIRECTIONS_8).map(|(_, c)| *c).collect()
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(INPUT).unwrap();
+        assert_eq!(neighbours_of(&grid, (0, 0)), vec!['.', '#', '.']);
+
+        let sparse = SparseGrid::parse(INPUT, |c| (c == '#').then_some(c));
+        assert_eq!(neighbours_of(&sparse, (1, 1)), vec!['#', '#']);
+
+        let infinite = InfiniteGrid::new(grid);
+        assert_eq!(neighbours_of(&infinite, (0, 0)), vec!['.', '.', '.', '#', '.', '.', '.', '#']);
+    }
+
+    #[test]
+    fn test_find_and_render() {
+        let grid = Grid::new(INPUT).unwrap();
+        let sparse = SparseGrid::parse(INPUT, |c| (c == '#').then_some(c));
+        assert_eq!(grid.find_where(|c| *c == '#'), vec![(0, 0), (1, 1), (2, 2)]);
+
+        let mut found = sparse.find_where(|c| *c == '#');
+        found.sort();
+        assert_eq!(found, vec![(0, 0), (1, 1), (2, 2)]);
+
+        let render = |c: Option<&char>| c.copied().unwrap_or('.');
+        assert_eq!(sparse.render_with(render).unwrap().plain().to_string(), INPUT);
+        assert_eq!(grid.render_area((1, 1), (3, 1), render).plain().to_string(), "#..");
+        assert!(SparseGrid::<char>::new().render_with(render).is_none());
+    }
+}
@@ -0,0 +1,593 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::{Animation, Color, Frame, Grid};
+
+type Rgb = [u8; 3];
+
+const CURSOR: Rgb = [255, 255, 255];
+const FALLBACK: [Rgb; 8] = [
+    [230, 85, 70],
+    [90, 180, 90],
+    [240, 200, 60],
+    [70, 130, 220],
+    [190, 100, 200],
+    [70, 190, 200],
+    [240, 140, 50],
+    [160, 160, 160],
+];
+
+impl Color {
+    pub fn rgb(self) -> Rgb {
+        match self {
+            Color::Red => [220, 50, 47],
+            Color::Green => [80, 200, 80],
+            Color::Yellow => [250, 210, 40],
+            Color::Blue => [40, 120, 240],
+            Color::Magenta => [210, 60, 200],
+            Color::Cyan => [40, 200, 210],
+            Color::White => [245, 245, 245],
+        }
+    }
+}
+
+/** how grid cells are drawn in images: the size of a cell in pixels and a colour per character */
+#[derive(Clone, Debug)]
+pub struct ImageStyle {
+    cell_size: usize,
+    palette: HashMap<char, Rgb>,
+}
+
+impl ImageStyle {
+    /** `.` is drawn dark and `#` light; other characters get a colour derived from their code */
+    pub fn new(cell_size: usize) -> Self {
+        let palette = HashMap::from([('.', [24, 24, 32]), ('#', [200, 200, 200])]);
+        Self { cell_size: cell_size.max(1), palette }
+    }
+
+    pub fn color(mut self, c: char, rgb: Rgb) -> Self {
+        self.palette.insert(c, rgb);
+        self
+    }
+
+    fn cell_color(&self, frame: &Frame, x: usize, y: usize) -> Rgb {
+        if frame.cursor_position() == Some((x, y)) {
+            return CURSOR;
+        }
+        match frame.cell(x, y) {
+            (_, Some(color)) => color.rgb(),
+            (c, None) => self.palette.get(&c).copied().unwrap_or(FALLBACK[c as usize % FALLBACK.len()]),
+        }
+    }
+
+    /** the pixels of a frame, row by row */
+    fn pixels(&self, frame: &Frame) -> (usize, usize, Vec<Rgb>) {
+        let grid = frame.grid();
+        let (width, height) = (grid.width() * self.cell_size, grid.height() * self.cell_size);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(self.cell_color(frame, x / self.cell_size, y / self.cell_size));
+            }
+        }
+        (width, height, pixels)
+    }
+}
+
+impl Grid {
+    pub fn save_png(&self, path: impl AsRef<Path>, style: &ImageStyle) -> io::Result<()> {
+        self.render().save_png(path, style)
+    }
+}
+
+impl Frame {
+    pub fn to_png(&self, style: &ImageStyle) -> Vec<u8> {
+        let (width, height, pixels) = style.pixels(self);
+        png(width, height, &pixels)
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>, style: &ImageStyle) -> io::Result<()> {
+        fs::write(path, self.to_png(style))
+    }
+}
+
+impl Animation {
+    /** encodes all frames as a looping GIF; fails if the frames use more than 256 colours or
+    are larger than 65535 pixels in either direction */
+    pub fn to_gif(&self, style: &ImageStyle, fps: u32) -> io::Result<Vec<u8>> {
+        let frames: Vec<_> = self.frames().iter().map(|frame| style.pixels(frame)).collect();
+        let delay = (100 / fps.max(1)).max(1) as u16;
+        gif(&frames, delay)
+    }
+
+    pub fn save_gif(&self, path: impl AsRef<Path>, style: &ImageStyle, fps: u32) -> io::Result<()> {
+        fs::write(path, self.to_gif(style, fps)?)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn png(width: usize, height: usize, pixels: &[Rgb]) -> Vec<u8> {
+    let mut raw = Vec::with_capacity(height * (width * 3 + 1));
+    for row in pixels.chunks(width.max(1)) {
+        raw.push(0);
+        raw.extend(row.iter().flatten());
+    }
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend((width as u32).to_be_bytes());
+    ihdr.extend((height as u32).to_be_bytes());
+    ihdr.extend([8, 2, 0, 0, 0]);
+
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    png_chunk(&mut out, b"IHDR", &ihdr);
+    png_chunk(&mut out, b"IDAT", &zlib(&raw));
+    png_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+/** LSB-first bit writer, as used by deflate and GIF's LZW */
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    len: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        self.buffer |= value << self.len;
+        self.len += bits;
+        while self.len >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    /** writes a huffman code, which deflate stores most significant bit first */
+    fn write_code(&mut self, code: u32, bits: u32) {
+        self.write(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+const WINDOW: usize = 32 * 1024;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 64;
+
+/** zlib stream with a single deflate block using the fixed huffman codes */
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+    bits.write(1, 1);
+    bits.write(1, 2);
+
+    // positions of earlier occurrences of three bytes, chained from the most recent one
+    let mut head: HashMap<[u8; 3], usize> = HashMap::new();
+    let mut prev = vec![usize::MAX; data.len()];
+    let insert = |head: &mut HashMap<[u8; 3], usize>, prev: &mut [usize], i: usize| {
+        if i + 3 <= data.len() {
+            prev[i] = head.insert([data[i], data[i + 1], data[i + 2]], i).unwrap_or(usize::MAX);
+        }
+    };
+
+    let mut i = 0;
+    while i < data.len() {
+        let (mut best_len, mut best_dist) = (0, 0);
+        if i + 3 <= data.len() {
+            let mut candidate = head.get(&[data[i], data[i + 1], data[i + 2]]).copied().unwrap_or(usize::MAX);
+            let mut chain = 0;
+            while candidate != usize::MAX && i - candidate <= WINDOW && chain < MAX_CHAIN {
+                let len = data[candidate..].iter().zip(&data[i..]).take(MAX_MATCH).take_while(|(a, b)| a == b).count();
+                if len > best_len {
+                    (best_len, best_dist) = (len, i - candidate);
+                }
+                candidate = prev[candidate];
+                chain += 1;
+            }
+        }
+
+        if best_len >= 3 {
+            write_match(&mut bits, best_len, best_dist);
+            for j in i..i + best_len {
+                insert(&mut head, &mut prev, j);
+            }
+            i += best_len;
+        } else {
+            write_literal(&mut bits, u32::from(data[i]));
+            insert(&mut head, &mut prev, i);
+            i += 1;
+        }
+    }
+    write_literal(&mut bits, 256);
+
+    let mut out = vec![0x78, 0x01];
+    out.extend(bits.finish());
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+fn write_literal(bits: &mut BitWriter, value: u32) {
+    match value {
+        0..=143 => bits.write_code(0x30 + value, 8),
+        144..=255 => bits.write_code(0x190 + value - 144, 9),
+        256..=279 => bits.write_code(value - 256, 7),
+        _ => bits.write_code(0xc0 + value - 280, 8),
+    }
+}
+
+fn write_match(bits: &mut BitWriter, len: usize, dist: usize) {
+    let code = LENGTH_BASE.iter().rposition(|&base| usize::from(base) <= len).unwrap();
+    write_literal(bits, 257 + code as u32);
+    bits.write((len - usize::from(LENGTH_BASE[code])) as u32, u32::from(LENGTH_EXTRA[code]));
+
+    let code = DISTANCE_BASE.iter().rposition(|&base| usize::from(base) <= dist).unwrap();
+    bits.write_code(code as u32, 5);
+    bits.write((dist - usize::from(DISTANCE_BASE[code])) as u32, u32::from(DISTANCE_EXTRA[code]));
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn gif(frames: &[(usize, usize, Vec<Rgb>)], delay: u16) -> io::Result<Vec<u8>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidInput, message.to_string());
+
+    let mut colors: Vec<Rgb> = Vec::new();
+    let mut index: HashMap<Rgb, u8> = HashMap::new();
+    for pixel in frames.iter().flat_map(|(_, _, pixels)| pixels) {
+        if !index.contains_key(pixel) {
+            if colors.len() == 256 {
+                return Err(invalid("a GIF can not have more than 256 colours"));
+            }
+            index.insert(*pixel, colors.len() as u8);
+            colors.push(*pixel);
+        }
+    }
+
+    let width = frames.iter().map(|(w, _, _)| *w).max().unwrap_or(1).max(1);
+    let height = frames.iter().map(|(_, h, _)| *h).max().unwrap_or(1).max(1);
+    let (width, height) = match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(w), Ok(h)) => (w, h),
+        _ => return Err(invalid("a GIF can not be larger than 65535 pixels")),
+    };
+
+    // the colour table has 2^(depth) entries, with a depth of at least 2 for LZW
+    let depth = (usize::BITS - colors.len().max(4).saturating_sub(1).leading_zeros()).max(2);
+    colors.resize(1 << depth, [0, 0, 0]);
+
+    let mut out = b"GIF89a".to_vec();
+    out.extend(width.to_le_bytes());
+    out.extend(height.to_le_bytes());
+    out.extend([0xf0 | (depth - 1) as u8, 0, 0]);
+    out.extend(colors.iter().flatten());
+    out.extend(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    for (w, h, pixels) in frames {
+        out.extend([0x21, 0xf9, 0x04, 0x04]);
+        out.extend(delay.to_le_bytes());
+        out.extend([0x00, 0x00]);
+
+        out.push(0x2c);
+        out.extend([0, 0, 0, 0]);
+        out.extend((*w as u16).to_le_bytes());
+        out.extend((*h as u16).to_le_bytes());
+        out.push(0);
+
+        out.push(depth as u8);
+        let indices: Vec<u8> = pixels.iter().map(|pixel| index[pixel]).collect();
+        for block in lzw(&indices, depth).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend(block);
+        }
+        out.push(0);
+    }
+
+    out.push(0x3b);
+    Ok(out)
+}
+
+const MAX_CODE: u16 = 4096;
+
+/** variable-length LZW as used by GIF, starting with codes of `min_size + 1` bits */
+fn lzw(indices: &[u8], min_size: u32) -> Vec<u8> {
+    let clear = 1u16 << min_size;
+    let end = clear + 1;
+
+    let mut bits = BitWriter::default();
+    let mut dictionary: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut size = min_size + 1;
+
+    bits.write(u32::from(clear), size);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        bits.write(u32::from(end), size);
+        return bits.finish();
+    };
+
+    let mut prefix = u16::from(first);
+    for &index in rest {
+        if let Some(&code) = dictionary.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+
+        bits.write(u32::from(prefix), size);
+        if next >= 1 << size && size < 12 {
+            size += 1;
+        }
+
+        if next < MAX_CODE {
+            dictionary.insert((prefix, index), next);
+            next += 1;
+        } else {
+            bits.write(u32::from(clear), size);
+            dictionary.clear();
+            next = end + 1;
+            size = min_size + 1;
+        }
+        prefix = u16::from(index);
+    }
+
+    bits.write(u32::from(prefix), size);
+    if next >= 1 << size && size < 12 {
+        size += 1;
+    }
+    bits.write(u32::from(end), size);
+    bits.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /** a plain LZW decoder, following the GIF specification */
+    fn unlzw(data: &[u8], min_size: u32) -> Vec<u8> {
+        let clear = 1usize << min_size;
+        let (mut pos, mut size) = (0usize, min_size + 1);
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut previous: Option<Vec<u8>> = None;
+        let mut out = Vec::new();
+
+        loop {
+            let mut code = 0;
+            for bit in 0..size as usize {
+                code |= usize::from(data[(pos + bit) / 8] >> ((pos + bit) % 8) & 1) << bit;
+            }
+            pos += size as usize;
+
+            if code == clear {
+                table = (0..clear).map(|i| vec![i as u8]).chain([vec![], vec![]]).collect();
+                (size, previous) = (min_size + 1, None);
+                continue;
+            }
+            if code == clear + 1 {
+                return out;
+            }
+
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [previous.clone(), vec![previous[0]]].concat(),
+                (None, None) => panic!("invalid code {code}"),
+            };
+            out.extend(&entry);
+            if let Some(previous) = previous {
+                table.push([previous, vec![entry[0]]].concat());
+                if table.len() == 1 << size && size < 12 {
+                    size += 1;
+                }
+            }
+            previous = Some(entry);
+        }
+    }
+
+    /** LSB-first bit reader, the counterpart of `BitWriter` */
+    struct BitReader<'a> {
+        data: &'a [u8],
+        pos: usize,
+    }
+
+    impl BitReader<'_> {
+        fn read(&mut self, bits: u32) -> usize {
+            let mut value = 0;
+            for bit in 0..bits as usize {
+                value |= usize::from(self.data[self.pos / 8] >> (self.pos % 8) & 1) << bit;
+                self.pos += 1;
+            }
+            value
+        }
+
+        /** reads a huffman code, most significant bit first */
+        fn read_code(&mut self, bits: u32) -> usize {
+            (0..bits).fold(0, |code, _| code << 1 | self.read(1))
+        }
+    }
+
+    /** a zlib decoder for stored and fixed huffman blocks, checking the header and checksum */
+    fn inflate(data: &[u8]) -> Vec<u8> {
+        assert_eq!(data[0] & 0x0f, 8, "compression method");
+        assert_eq!(u16::from_be_bytes([data[0], data[1]]) % 31, 0, "header check");
+        let mut bits = BitReader { data: &data[2..], pos: 0 };
+        let mut out = Vec::new();
+
+        loop {
+            let last = bits.read(1) == 1;
+            match bits.read(2) {
+                0 => {
+                    bits.pos = bits.pos.div_ceil(8) * 8;
+                    let len = bits.read(16);
+                    assert_eq!(bits.read(16), !len & 0xffff, "stored block length");
+                    out.extend(&bits.data[bits.pos / 8..bits.pos / 8 + len]);
+                    bits.pos += len * 8;
+                }
+                1 => loop {
+                    match fixed_symbol(&mut bits) {
+                        symbol @ 0..=255 => out.push(symbol as u8),
+                        256 => break,
+                        symbol => {
+                            let code = symbol - 257;
+                            let len = usize::from(LENGTH_BASE[code]) + bits.read(u32::from(LENGTH_EXTRA[code]));
+                            let code = bits.read_code(5);
+                            let dist = usize::from(DISTANCE_BASE[code]) + bits.read(u32::from(DISTANCE_EXTRA[code]));
+                            for _ in 0..len {
+                                out.push(out[out.len() - dist]);
+                            }
+                        }
+                    }
+                },
+                kind => panic!("unsupported block type {kind}"),
+            }
+            if last {
+                break;
+            }
+        }
+
+        assert_eq!(bits.data[bits.pos.div_ceil(8)..], adler32(&out).to_be_bytes());
+        out
+    }
+
+    /** decodes a literal/length symbol with the fixed huffman codes */
+    fn fixed_symbol(bits: &mut BitReader) -> usize {
+        let code = bits.read_code(7);
+        if code <= 0x17 {
+            return 256 + code;
+        }
+        let code = code << 1 | bits.read(1);
+        match code {
+            0x30..=0xbf => code - 0x30,
+            0xc0..=0xc7 => 280 + code - 0xc0,
+            _ => 144 + (code << 1 | bits.read(1)) - 0x190,
+        }
+    }
+
+    /** the data of all chunks of a kind, concatenated */
+    fn png_chunks(png: &[u8], kind: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut pos = 8;
+        while pos < png.len() {
+            let len = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+            if &png[pos + 4..pos + 8] == kind {
+                data.extend(&png[pos + 8..pos + 8 + len]);
+            }
+            pos += len + 12;
+        }
+        data
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn test_lzw_round_trip() {
+        let mut rng = crate::template::testing::Rng::new(7);
+        let random: Vec<u8> = (0..20_000).map(|_| rng.below(16) as u8).collect();
+        for indices in [vec![], vec![3], vec![0; 10_000], random] {
+            assert_eq!(unlzw(&lzw(&indices, 4), 4), indices);
+        }
+    }
+
+    #[test]
+    fn test_png_header() {
+        let grid = Grid::new("#.\n.#").unwrap();
+        let png = grid.render().to_png(&ImageStyle::new(3));
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 6, 0, 0, 0, 6]);
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+    }
+
+    #[test]
+    fn test_zlib_round_trip() {
+        let mut rng = crate::template::testing::Rng::new(3);
+        let random: Vec<u8> = (0..100_000).map(|_| rng.below(256) as u8).collect();
+        let repeated: Vec<u8> = (0..100_000).map(|i| (i % 251) as u8).collect();
+        for data in [vec![], vec![7], b"abcabcabcabc".to_vec(), vec![0; 70_000], random, repeated] {
+            assert_eq!(inflate(&zlib(&data)), data);
+        }
+    }
+
+    #[test]
+    fn test_png_scanlines() {
+        let mut rng = crate::template::testing::Rng::new(5);
+        // the largest frame has 200 rows of 1 + 600 * 3 bytes, i.e. more than 64 KiB of scanlines
+        for (width, height, cell_size) in [(1, 1, 1), (3, 2, 4), (40, 7, 1), (150, 100, 2)] {
+            let rows: Vec<String> =
+                (0..height).map(|_| (0..width).map(|_| ['#', '.', 'o'][rng.below(3)]).collect()).collect();
+            let frame = Grid::new(&rows.join("\n")).unwrap().render();
+            let style = ImageStyle::new(cell_size);
+
+            let (pixel_width, _, pixels) = style.pixels(&frame);
+            let scanlines: Vec<u8> =
+                pixels.chunks(pixel_width).flat_map(|row| [0].into_iter().chain(row.iter().flatten().copied())).collect();
+            assert_eq!(inflate(&png_chunks(&frame.to_png(&style), b"IDAT")), scanlines);
+        }
+    }
+
+    #[test]
+    fn test_gif_colour_limit() {
+        let mut animation = Animation::new();
+        let input: String = (0x100..0x300).filter_map(char::from_u32).collect();
+        let mut style = ImageStyle::new(1);
+        for (i, c) in input.chars().enumerate() {
+            style = style.color(c, [i as u8, (i / 256) as u8, 0]);
+        }
+        animation.record(Grid::new(&input).unwrap().render());
+        assert!(animation.to_gif(&style, 10).is_err());
+    }
+}
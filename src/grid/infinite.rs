@@ -0,0 +1,64 @@
+use super::{Grid, GridLike, Point};
+
+/** a dense tile repeated in every direction */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InfiniteGrid<T = char> {
+    tile: Grid<T>,
+}
+
+impl<T> InfiniteGrid<T> {
+    pub fn new(tile: Grid<T>) -> Self {
+        Self { tile }
+    }
+
+    pub fn tile(&self) -> &Grid<T> {
+        &self.tile
+    }
+
+    /** the position within the tile that a point maps to */
+    pub fn wrap(&self, (x, y): Point) -> (usize, usize) {
+        let (width, height) = (self.tile.width() as i64, self.tile.height() as i64);
+        (x.rem_euclid(width) as usize, y.rem_euclid(height) as usize)
+    }
+
+    pub fn get(&self, p: Point) -> &T {
+        let (x, y) = self.wrap(p);
+        &self.tile.grid[y][x]
+    }
+
+    /** sets a cell of the tile, which changes it in every repetition */
+    pub fn set(&mut self, p: Point, value: T) {
+        let (x, y) = self.wrap(p);
+        self.tile.set(x, y, value);
+    }
+}
+
+impl<T> GridLike for InfiniteGrid<T> {
+    type Cell = T;
+
+    fn cell(&self, p: Point) -> Option<&T> {
+        Some(self.get(p))
+    }
+
+    /** the bounds of the tile at the origin */
+    fn bounds(&self) -> Option<(Point, Point)> {
+        self.tile.bounds()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infinite_grid() {
+        let mut grid = InfiniteGrid::new(Grid::new("ab\ncd").unwrap());
+        assert_eq!(grid.wrap((-1, -3)), (1, 1));
+        assert_eq!(*grid.get((4, 1)), 'c');
+        assert_eq!(*grid.get((-1_000_001, 1_000_000)), 'b');
+
+        grid.set((3, 3), 'x');
+        assert_eq!(*grid.get((1, 1)), 'x');
+        assert_eq!(grid.render_area((0, 0), (3, 1), |c| *c.unwrap()).plain().to_string(), "abab\ncxcx");
+    }
+}
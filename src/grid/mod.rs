@@ -0,0 +1,185 @@
+use std::fmt::Display;
+
+pub use bit_grid::{BitGrid, NeighbourCounts};
+pub use grid_like::{GridLike, Point, DIRECTIONS_4, DIRECTIONS_8};
+#[cfg(feature = "images")]
+pub use image::ImageStyle;
+pub use infinite::InfiniteGrid;
+pub use render::{Animation, Color, Frame};
+pub use sparse::SparseGrid;
+pub use view::GridView;
+
+mod bit_grid;
+mod grid_like;
+#[cfg(feature = "images")]
+mod image;
+mod infinite;
+mod render;
+mod sparse;
+mod view;
+
+/** a dense, rectangular grid, stored row by row */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T = char> {
+    grid: Vec<Vec<T>>,
+    width: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GridError {
+    Empty,
+    Ragged { line: usize, expected: usize, found: usize },
+}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "grid is empty"),
+            GridError::Ragged { line, expected, found } => write!(
+                f,
+                "line {line} has {found} characters, expected {expected} like the first line"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+impl Grid {
+    /** parses a rectangular grid of characters, one row per line */
+    pub fn new(input: &str) -> Result<Self, GridError> {
+        Self::from_rows(input.lines().map(|line| line.chars().collect()).collect())
+    }
+
+    /** starts a frame of the grid, which can be highlighted, printed or recorded */
+    pub fn render(&self) -> Frame {
+        Frame::new(self.clone())
+    }
+}
+
+impl<T> Grid<T> {
+    /** builds a grid from rows, which must all have the same, non-zero length */
+    pub fn from_rows(grid: Vec<Vec<T>>) -> Result<Self, GridError> {
+        let width = grid.first().map(Vec::len).filter(|w| *w > 0).ok_or(GridError::Empty)?;
+
+        if let Some((i, row)) = grid.iter().enumerate().find(|(_, row)| row.len() != width) {
+            return Err(GridError::Ragged { line: i + 1, expected: width, found: row.len() });
+        }
+
+        Ok(Self { grid, width })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.len()
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        self.grid[y][x] = value;
+    }
+
+    pub fn rows(&self) -> &[Vec<T>] {
+        &self.grid
+    }
+
+    /** is_valid checks if a position is within the grid */
+    pub fn is_valid(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (y as usize) < self.height() && (x as usize) < self.width
+    }
+
+    /** applies a function to every cell, e.g. to parse digits */
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        let grid = self.grid.iter().map(|row| row.iter().map(&mut f).collect()).collect();
+        Grid { grid, width: self.width }
+    }
+}
+
+impl<T: Copy> Grid<T> {
+    pub fn get(&self, x: usize, y: usize) -> T {
+        self.grid[y][x]
+    }
+}
+
+impl<T: Clone + PartialEq> Grid<T> {
+    /** find the positions of a value in the grid */
+    pub fn find(&self, value: T) -> Vec<(usize, usize)> {
+        let mut positions = Vec::new();
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if *cell == value {
+                    positions.push((x, y));
+                }
+            }
+        }
+        positions
+    }
+
+    /** given an origin, a length, and a direction, returns the values on this path */
+    pub fn path(&self, origin: (i32, i32), length: i32, direction: (i32, i32)) -> Vec<T> {
+        let mut path = Vec::new();
+        let (dx, dy) = direction;
+        let (mut x, mut y) = origin;
+        for _ in 0..length {
+            if !self.is_valid(x, y) {
+                break;
+            }
+            path.push(self.grid[y as usize][x as usize].clone());
+            x += dx;
+            y += dy;
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_structure() {
+        let input = "ABC\nDEF\nGHI";
+        let grid = Grid::new(input).unwrap();
+        assert_eq!(grid.grid, vec![vec!['A', 'B', 'C'], vec!['D', 'E', 'F'], vec!['G', 'H', 'I']]);
+    }
+
+    #[test]
+    fn test_grid_find() {
+        let input = "TCGAG\nAGTAC\nGACGT";
+        let grid = Grid::new(input).unwrap();
+        assert_eq!(grid.find('A').len(), 4);
+    }
+
+    #[test]
+    fn test_grid_is_valid() {
+        let input = "123\n456\n789";
+        let grid = Grid::new(input).unwrap();
+        assert!(grid.is_valid(0, 0));
+        assert!(!grid.is_valid(3, -1));
+    }
+
+    #[test]
+    fn test_grid_rejects_invalid_input() {
+        assert_eq!(Grid::new("").err(), Some(GridError::Empty));
+        assert_eq!(Grid::new("\nABC").err(), Some(GridError::Empty));
+        assert_eq!(
+            Grid::new("ABC\nDE\nFGH").err(),
+            Some(GridError::Ragged { line: 2, expected: 3, found: 2 })
+        );
+        assert_eq!(
+            Grid::new("ABC\n\nDEF").err(),
+            Some(GridError::Ragged { line: 2, expected: 3, found: 0 })
+        );
+    }
+
+    #[test]
+    fn test_grid_path() {
+        let input = "123\n456\n789";
+        let grid = Grid::new(input).unwrap();
+        assert_eq!(grid.path((0, 0), 3, (1, 0)), vec!['1', '2', '3']);
+        assert_eq!(grid.path((0, 0), 3, (0, 1)), vec!['1', '4', '7']);
+        assert_eq!(grid.path((0, 0), 3, (1, 1)), vec!['1', '5', '9']);
+    }
+}
@@ -0,0 +1,265 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::io::{self, BufRead, Write};
+use std::thread;
+use std::time::Duration;
+
+use super::Grid;
+use crate::template::config::{Config, OutputFormat};
+use crate::template::{ansi, ANSI_BOLD, ANSI_RESET};
+
+const ANSI_INVERSE: &str = "\x1b[7m";
+const ANSI_CLEAR: &str = "\x1b[H\x1b[2J";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Color::Red => "\x1b[31m",
+            Color::Green => "\x1b[32m",
+            Color::Yellow => "\x1b[33m",
+            Color::Blue => "\x1b[34m",
+            Color::Magenta => "\x1b[35m",
+            Color::Cyan => "\x1b[36m",
+            Color::White => "\x1b[37m",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Style {
+    color: Option<Color>,
+    glyph: Option<char>,
+}
+
+/** a snapshot of a grid with highlighted cells; prints with ANSI colours via Display, unless `output.format` is plain */
+#[derive(Clone, Debug)]
+pub struct Frame {
+    grid: Grid,
+    styles: HashMap<(usize, usize), Style>,
+    cursor: Option<(usize, usize)>,
+    caption: Option<String>,
+    plain: bool,
+}
+
+impl Frame {
+    pub(super) fn new(grid: Grid) -> Self {
+        Self { grid, styles: HashMap::new(), cursor: None, caption: None, plain: false }
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /** colours the given cells; later highlights win over earlier ones */
+    pub fn highlight<I>(mut self, cells: I, color: Color) -> Self where I: IntoIterator, I::Item: Borrow<(usize, usize)> {
+        for cell in cells {
+            self.styles.entry(*cell.borrow()).or_default().color = Some(color);
+        }
+        self
+    }
+
+    /** replaces the character of the given cells, e.g. with `#` for a path, and colours them */
+    pub fn mark<I>(mut self, cells: I, glyph: char, color: Color) -> Self where I: IntoIterator, I::Item: Borrow<(usize, usize)> {
+        for cell in cells {
+            self.styles.insert(*cell.borrow(), Style { color: Some(color), glyph: Some(glyph) });
+        }
+        self
+    }
+
+    /** shows the cell in inverted colours, e.g. the current position of a guard */
+    pub fn cursor(mut self, cell: (usize, usize)) -> Self {
+        self.cursor = Some(cell);
+        self
+    }
+
+    /** a line of text printed below the grid */
+    pub fn caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /** prints without escape codes; marks still replace characters */
+    pub fn plain(mut self) -> Self {
+        self.plain = true;
+        self
+    }
+
+    /** the character of a cell after marks are applied, along with its colour */
+    pub fn cell(&self, x: usize, y: usize) -> (char, Option<Color>) {
+        let style = self.styles.get(&(x, y)).copied().unwrap_or_default();
+        (style.glyph.unwrap_or_else(|| self.grid.get(x, y)), style.color)
+    }
+
+    pub fn cursor_position(&self) -> Option<(usize, usize)> {
+        self.cursor
+    }
+
+    /** writes the frame with escape codes for the pretty format, unless the frame was made plain */
+    fn write(&self, out: &mut impl fmt::Write, format: OutputFormat) -> fmt::Result {
+        let pretty = format == OutputFormat::Pretty && !self.plain;
+        for y in 0..self.grid.height() {
+            if y > 0 {
+                writeln!(out)?;
+            }
+            for x in 0..self.grid.width() {
+                let (c, color) = self.cell(x, y);
+                if !pretty {
+                    write!(out, "{c}")?;
+                } else if self.cursor == Some((x, y)) {
+                    write!(out, "{ANSI_INVERSE}{ANSI_BOLD}{c}{ANSI_RESET}")?;
+                } else if let Some(color) = color {
+                    write!(out, "{}{ANSI_BOLD}{c}{ANSI_RESET}", color.code())?;
+                } else {
+                    write!(out, "{c}")?;
+                }
+            }
+        }
+        if let Some(caption) = &self.caption {
+            write!(out, "\n{caption}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, Config::get().output.format)
+    }
+}
+
+/** frames recorded during a simulation, to be played back or stepped through */
+#[derive(Clone, Debug, Default)]
+pub struct Animation {
+    frames: Vec<Frame>,
+}
+
+impl Animation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /** plays all frames in the terminal at the given frames per second */
+    pub fn play(&self, fps: u32) {
+        let delay = Duration::from_secs_f64(1.0 / f64::from(fps.max(1)));
+        let mut stdout = io::stdout().lock();
+        for i in 0..self.frames.len() {
+            let _ = self.draw(&mut stdout, i).and_then(|()| stdout.flush());
+            thread::sleep(delay);
+        }
+    }
+
+    /** steps through frames interactively: enter for the next frame, `b` for the previous one,
+    a number to jump to a frame and `q` to quit */
+    pub fn step(&self) {
+        let _ = self.step_with(io::stdin().lock(), io::stdout().lock());
+    }
+
+    fn step_with(&self, input: impl BufRead, mut out: impl Write) -> io::Result<()> {
+        if self.frames.is_empty() {
+            return Ok(());
+        }
+
+        let mut lines = input.lines();
+        let mut i = 0;
+        loop {
+            self.draw(&mut out, i)?;
+            write!(out, "\n[enter] next, [b] back, [<n>] jump, [q] quit > ")?;
+            out.flush()?;
+
+            let Some(line) = lines.next().transpose()? else {
+                return Ok(());
+            };
+            match line.trim() {
+                "q" => return Ok(()),
+                "b" => i = i.saturating_sub(1),
+                "" => i = (i + 1).min(self.frames.len() - 1),
+                n => {
+                    if let Ok(n) = n.parse::<usize>() {
+                        i = n.clamp(1, self.frames.len()) - 1;
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw(&self, out: &mut impl Write, i: usize) -> io::Result<()> {
+        write!(out, "{}{}\nframe {}/{}", ansi(ANSI_CLEAR), self.frames[i], i + 1, self.frames.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn grid() -> Grid {
+        Grid::new("...\n.#.\n...").unwrap()
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let frame = grid().render().mark([(0, 0), (1, 0)], 'o', Color::Red).cursor((2, 2)).caption("step 1").plain();
+        let mut out = String::new();
+        frame.write(&mut out, OutputFormat::Pretty).unwrap();
+        assert_eq!(out, "oo.\n.#.\n...\nstep 1");
+    }
+
+    #[test]
+    fn test_render_highlights() {
+        let visited: HashSet<(usize, usize)> = [(1, 1)].into();
+        let frame = grid().render().highlight(&visited, Color::Red).highlight([(1, 1)], Color::Blue).cursor((0, 2));
+        assert_eq!(frame.cell(1, 1), ('#', Some(Color::Blue)));
+
+        let mut pretty = String::new();
+        frame.write(&mut pretty, OutputFormat::Pretty).unwrap();
+        assert_eq!(pretty, format!("...\n.\x1b[34m{ANSI_BOLD}#{ANSI_RESET}.\n{ANSI_INVERSE}{ANSI_BOLD}.{ANSI_RESET}.."));
+
+        let mut plain = String::new();
+        frame.write(&mut plain, OutputFormat::Plain).unwrap();
+        assert_eq!(plain, "...\n.#.\n...");
+    }
+
+    #[test]
+    fn test_animation_step() {
+        let mut animation = Animation::new();
+        for i in 0..3 {
+            let mut grid = grid();
+            grid.set(i, 0, '@');
+            animation.record(grid.render().plain());
+        }
+
+        let mut out = Vec::new();
+        animation.step_with("\n\n\nb\n1\nq\n".as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let frames: Vec<&str> = out.lines().filter_map(|line| line.strip_prefix("frame ")).collect();
+        assert_eq!(frames, ["1/3", "2/3", "3/3", "3/3", "2/3", "1/3"]);
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use super::{GridLike, Point};
+
+/** a grid that only stores occupied cells, for unbounded or mostly empty coordinates */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T = char> {
+    cells: HashMap<Point, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self { cells: HashMap::new() }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /** parses lines of characters, keeping the cells for which `f` returns a value */
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> Option<T>) -> Self {
+        input
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| line.chars().enumerate().map(move |(x, c)| ((x as i64, y as i64), c)))
+            .filter_map(|(p, c)| f(c).map(|value| (p, value)))
+            .collect()
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn insert(&mut self, p: Point, value: T) -> Option<T> {
+        self.cells.insert(p, value)
+    }
+
+    pub fn remove(&mut self, p: Point) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        Self { cells: iter.into_iter().collect() }
+    }
+}
+
+impl<T> GridLike for SparseGrid<T> {
+    type Cell = T;
+
+    fn cell(&self, p: Point) -> Option<&T> {
+        self.get(p)
+    }
+
+    fn bounds(&self) -> Option<(Point, Point)> {
+        let mut points = self.cells.keys();
+        let first = *points.next()?;
+        Some(points.fold((first, first), |((x0, y0), (x1, y1)), &(x, y)| {
+            ((x0.min(x), y0.min(y)), (x1.max(x), y1.max(y)))
+        }))
+    }
+
+    /** every occupied cell, in no particular order */
+    fn cells(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(p, value)| (*p, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid: SparseGrid<u8> = [((0, 0), 1), ((-3, 2_000_000), 2)].into_iter().collect();
+        assert_eq!(grid.bounds(), Some(((-3, 0), (0, 2_000_000))));
+        assert_eq!(grid.get((-3, 2_000_000)), Some(&2));
+
+        grid.insert((5, -1), 3);
+        assert_eq!(grid.remove((0, 0)), Some(1));
+        assert!(!grid.contains((0, 0)));
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.bounds(), Some(((-3, -1), (5, 2_000_000))));
+    }
+}
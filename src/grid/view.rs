@@ -0,0 +1,251 @@
+use super::{Grid, GridLike, Point};
+
+/** a rotated, flipped or cropped view of a grid that borrows its cells instead of copying them */
+#[derive(Debug)]
+pub struct GridView<'a, T = char> {
+    grid: &'a Grid<T>,
+    // the source position of the view's top left cell, and the source steps for +x and +y in the view
+    origin: (isize, isize),
+    step_x: (isize, isize),
+    step_y: (isize, isize),
+    width: usize,
+    height: usize,
+}
+
+impl<T> Clone for GridView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for GridView<'_, T> {}
+
+impl<'a, T> GridView<'a, T> {
+    fn new(grid: &'a Grid<T>) -> Self {
+        Self { grid, origin: (0, 0), step_x: (1, 0), step_y: (0, 1), width: grid.width(), height: grid.height() }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn source(&self, x: usize, y: usize) -> (usize, usize) {
+        let (x, y) = (x as isize, y as isize);
+        let source_x = self.origin.0 + self.step_x.0 * x + self.step_y.0 * y;
+        let source_y = self.origin.1 + self.step_x.1 * x + self.step_y.1 * y;
+        (source_x as usize, source_y as usize)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &'a T {
+        assert!(x < self.width && y < self.height, "({x}, {y}) is outside of the view");
+        let (x, y) = self.source(x, y);
+        &self.grid.grid[y][x]
+    }
+
+    /** rotates the view by 90° clockwise */
+    pub fn rotate(self) -> Self {
+        let last_row = self.height as isize - 1;
+        Self {
+            origin: (self.origin.0 + self.step_y.0 * last_row, self.origin.1 + self.step_y.1 * last_row),
+            step_x: (-self.step_y.0, -self.step_y.1),
+            step_y: self.step_x,
+            width: self.height,
+            height: self.width,
+            ..self
+        }
+    }
+
+    /** mirrors the view from left to right */
+    pub fn flip_horizontal(self) -> Self {
+        let last_column = self.width as isize - 1;
+        Self {
+            origin: (self.origin.0 + self.step_x.0 * last_column, self.origin.1 + self.step_x.1 * last_column),
+            step_x: (-self.step_x.0, -self.step_x.1),
+            ..self
+        }
+    }
+
+    /** mirrors the view from top to bottom */
+    pub fn flip_vertical(self) -> Self {
+        let last_row = self.height as isize - 1;
+        Self {
+            origin: (self.origin.0 + self.step_y.0 * last_row, self.origin.1 + self.step_y.1 * last_row),
+            step_y: (-self.step_y.0, -self.step_y.1),
+            ..self
+        }
+    }
+
+    /** swaps rows and columns */
+    pub fn transpose(self) -> Self {
+        Self { step_x: self.step_y, step_y: self.step_x, width: self.height, height: self.width, ..self }
+    }
+
+    /** a rectangle of the view, or None if it is empty or does not fit */
+    pub fn crop(self, x: usize, y: usize, width: usize, height: usize) -> Option<Self> {
+        if width == 0 || height == 0 || x + width > self.width || y + height > self.height {
+            return None;
+        }
+        let (x, y) = (x as isize, y as isize);
+        Some(Self {
+            origin: (
+                self.origin.0 + self.step_x.0 * x + self.step_y.0 * y,
+                self.origin.1 + self.step_x.1 * x + self.step_y.1 * y,
+            ),
+            width,
+            height,
+            ..self
+        })
+    }
+
+    /** the view, then rotated by 90°, 180° and 270°, followed by the same four mirrored */
+    pub fn symmetries(self) -> [Self; 8] {
+        let r90 = self.rotate();
+        let r180 = r90.rotate();
+        let r270 = r180.rotate();
+        [
+            self,
+            r90,
+            r180,
+            r270,
+            self.flip_horizontal(),
+            r90.flip_horizontal(),
+            r180.flip_horizontal(),
+            r270.flip_horizontal(),
+        ]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &'a T> + 'a> + 'a {
+        let view = *self;
+        (0..self.height).map(move |y| (0..view.width).map(move |x| view.get(x, y)))
+    }
+
+    /** copies the cells of the view into a new grid */
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let grid = self.rows().map(|row| row.cloned().collect()).collect();
+        Grid { grid, width: self.width }
+    }
+}
+
+impl<T> GridLike for GridView<'_, T> {
+    type Cell = T;
+
+    fn cell(&self, (x, y): Point) -> Option<&T> {
+        let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        (x < self.width && y < self.height).then(|| self.get(x, y))
+    }
+
+    fn bounds(&self) -> Option<(Point, Point)> {
+        Some(((0, 0), (self.width as i64 - 1, self.height as i64 - 1)))
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn view(&self) -> GridView<'_, T> {
+        GridView::new(self)
+    }
+
+    /** all eight rotations and reflections of the grid, see `GridView::symmetries` */
+    pub fn symmetries(&self) -> [GridView<'_, T>; 8] {
+        self.view().symmetries()
+    }
+
+    /** shifts a row to the right, wrapping around; negative amounts shift to the left */
+    pub fn shift_row(&mut self, y: usize, by: isize) {
+        let by = by.rem_euclid(self.width as isize) as usize;
+        self.grid[y].rotate_right(by);
+    }
+
+    /** shifts a column down, wrapping around; negative amounts shift up */
+    pub fn shift_column(&mut self, x: usize, by: isize) {
+        let height = self.height();
+        let by = by.rem_euclid(height as isize) as usize;
+        // rotating by three reversals only needs swaps, so cells do not have to be cloned
+        self.reverse_column(x, 0, height);
+        self.reverse_column(x, 0, by);
+        self.reverse_column(x, by, height);
+    }
+
+    fn reverse_column(&mut self, x: usize, start: usize, end: usize) {
+        let (mut top, mut bottom) = (start, end);
+        while top + 1 < bottom {
+            bottom -= 1;
+            let (above, below) = self.grid.split_at_mut(bottom);
+            std::mem::swap(&mut above[top][x], &mut below[0][x]);
+            top += 1;
+        }
+    }
+
+    /** repeats the grid `columns` times horizontally and `rows` times vertically; panics if either count is zero,
+    as grids cannot be empty */
+    pub fn tile(&self, columns: usize, rows: usize) -> Grid<T>
+    where
+        T: Clone,
+    {
+        assert!(columns > 0 && rows > 0, "cannot tile a grid {columns}x{rows} times, grids cannot be empty");
+        let grid: Vec<Vec<T>> = (0..rows)
+            .flat_map(|_| self.grid.iter().map(|row| row.iter().cycle().take(row.len() * columns).cloned().collect()))
+            .collect();
+        Grid { grid, width: self.width * columns }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid {
+        Grid::new("abc\ndef").unwrap()
+    }
+
+    fn text(view: GridView) -> String {
+        view.rows().map(|row| row.collect::<String>()).collect::<Vec<_>>().join("\n")
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = grid();
+        assert_eq!(text(grid.view().rotate()), "da\neb\nfc");
+        assert_eq!(text(grid.view().rotate().rotate()), "fed\ncba");
+        assert_eq!(text(grid.view().flip_horizontal()), "cba\nfed");
+        assert_eq!(text(grid.view().flip_vertical()), "def\nabc");
+        assert_eq!(text(grid.view().transpose()), "ad\nbe\ncf");
+        assert_eq!(text(grid.view().rotate().crop(1, 1, 1, 2).unwrap()), "b\nc");
+        assert_eq!(text(grid.view().flip_vertical().crop(1, 0, 2, 1).unwrap().rotate()), "e\nf");
+        assert!(grid.view().crop(2, 0, 2, 1).is_none());
+    }
+
+    #[test]
+    fn test_symmetries() {
+        let grid = grid();
+        let mut symmetries: Vec<String> = grid.symmetries().into_iter().map(text).collect();
+        assert_eq!(symmetries[4], "cba\nfed");
+        symmetries.sort();
+        symmetries.dedup();
+        assert_eq!(symmetries.len(), 8);
+        assert_eq!(grid.view().transpose().to_grid(), Grid::new("ad\nbe\ncf").unwrap());
+    }
+
+    #[test]
+    fn test_shift_and_tile() {
+        let mut grid = grid();
+        grid.shift_row(0, 1);
+        grid.shift_column(2, -3);
+        assert_eq!(grid, Grid::new("caf\ndeb").unwrap());
+        grid.shift_column(0, 5);
+        assert_eq!(grid, Grid::new("daf\nceb").unwrap());
+        assert_eq!(grid.tile(2, 2).view().crop(2, 1, 3, 2).map(text).unwrap(), "bce\nfda");
+    }
+
+    #[test]
+    #[should_panic(expected = "grids cannot be empty")]
+    fn test_tile_rejects_empty_grids() {
+        let _ = grid().tile(0, 2);
+    }
+}
@@ -1,7 +1,15 @@
 pub mod template;
 
+pub mod collections;
+pub mod cycle;
 pub mod grid;
+pub mod linalg;
+pub mod math;
+pub mod memo;
 pub mod printer;
+pub mod ranges;
+pub mod text;
+pub mod vm;
 
 pub fn add_tuples(t1: (i32, i32), t2: (i32, i32)) -> (i32, i32) {
     (t1.0 + t2.0, t1.1 + t2.1)
@@ -0,0 +1,362 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::math::{checked_gcd, gcd, Signed};
+
+const OVERFLOW: &str = "rational arithmetic overflowed";
+
+/** an exact fraction, always stored in lowest terms with a positive denominator */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational<T = i128> {
+    numer: T,
+    denom: T,
+}
+
+impl<T: Signed> Rational<T> {
+    pub const ZERO: Self = Self { numer: T::ZERO, denom: T::ONE };
+    pub const ONE: Self = Self { numer: T::ONE, denom: T::ONE };
+
+    /** panics if the denominator is zero */
+    pub fn new(numer: T, denom: T) -> Self {
+        Self::checked_new(numer, denom).expect("denominator must not be zero")
+    }
+
+    /** None if the denominator is zero or the fraction does not fit once its sign is normalized */
+    pub fn checked_new(numer: T, denom: T) -> Option<Self> {
+        if denom == T::ZERO {
+            return None;
+        }
+        // the gcd only does not fit if both are multiples of `MIN`, then dividing by `MIN` reduces them just as well
+        let g = checked_gcd(numer, denom).unwrap_or(T::MIN);
+        let (numer, denom) = (numer / g, denom / g);
+        if denom.is_negative() {
+            Some(Self { numer: T::ZERO.checked_sub(numer)?, denom: T::ZERO.checked_sub(denom)? })
+        } else {
+            Some(Self { numer, denom })
+        }
+    }
+
+    pub fn numer(&self) -> T {
+        self.numer
+    }
+
+    pub fn denom(&self) -> T {
+        self.denom
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == T::ONE
+    }
+
+    pub fn to_integer(&self) -> Option<T> {
+        self.is_integer().then_some(self.numer)
+    }
+
+    /** the largest integer that is not greater than the fraction */
+    pub fn floor(&self) -> T {
+        let quotient = self.numer / self.denom;
+        if self.numer.rem_euclid(self.denom) != T::ZERO && self.numer.is_negative() {
+            quotient - T::ONE
+        } else {
+            quotient
+        }
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let g = gcd(self.denom, other.denom);
+        let numer = self.numer.checked_mul(other.denom / g)?.checked_add(other.numer.checked_mul(self.denom / g)?)?;
+        Self::checked_new(numer, (self.denom / g).checked_mul(other.denom)?)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(Self { numer: T::ZERO.checked_sub(other.numer)?, ..other })
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        // cancelling across first keeps the intermediate products small
+        let (g1, g2) = (gcd(self.numer, other.denom), gcd(other.numer, self.denom));
+        let numer = (self.numer / g1).checked_mul(other.numer / g2)?;
+        let denom = (self.denom / g2).checked_mul(other.denom / g1)?;
+        Self::checked_new(numer, denom)
+    }
+
+    /** None if `other` is zero or the result overflows */
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        self.checked_mul(Self::checked_new(other.denom, other.numer)?)
+    }
+}
+
+impl<T: Signed> From<T> for Rational<T> {
+    fn from(numer: T) -> Self {
+        Self { numer, denom: T::ONE }
+    }
+}
+
+impl<T: Signed> Display for Rational<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl<T: Signed> Ord for Rational<T> {
+    /** compares the integer parts first, then the fractional parts by their continued fractions, which never
+    overflows */
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ordering = self.floor().cmp(&other.floor());
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+
+        // compare a / b with c / d, all of them non-negative; taking reciprocals flips the ordering
+        let (mut a, mut b) = (self.numer.rem_euclid(self.denom), self.denom);
+        let (mut c, mut d) = (other.numer.rem_euclid(other.denom), other.denom);
+        let mut flipped = false;
+        loop {
+            let ordering = match (a / b).cmp(&(c / d)) {
+                Ordering::Equal => match (a % b == T::ZERO, c % d == T::ZERO) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    (false, false) => {
+                        (a, b, c, d) = (b, a % b, d, c % d);
+                        flipped = !flipped;
+                        continue;
+                    }
+                },
+                ordering => ordering,
+            };
+            return if flipped { ordering.reverse() } else { ordering };
+        }
+    }
+}
+
+impl<T: Signed> PartialOrd for Rational<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Signed> Neg for Rational<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::ZERO.checked_sub(self).expect(OVERFLOW)
+    }
+}
+
+macro_rules! rational_operator {
+    ($trait:ident, $method:ident, $checked:ident) => {
+        impl<T: Signed> $trait for Rational<T> {
+            type Output = Self;
+
+            /** panics on overflow or division by zero */
+            fn $method(self, other: Self) -> Self {
+                self.$checked(other).expect(OVERFLOW)
+            }
+        }
+    };
+}
+
+rational_operator!(Add, add, checked_add);
+rational_operator!(Sub, sub, checked_sub);
+rational_operator!(Mul, mul, checked_mul);
+rational_operator!(Div, div, checked_div);
+
+/* -------------------------------------------------------------------------- */
+
+/** the solutions of a system of linear equations */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Solution<T = i128> {
+    Unique(Vec<Rational<T>>),
+    None,
+    /** every `particular + t1 * free[0] + t2 * free[1] + ...` is a solution */
+    Infinite { particular: Vec<Rational<T>>, free: Vec<Vec<Rational<T>>> },
+}
+
+/** solves `a * x = b` exactly with Gaussian elimination; `a` has one row per equation */
+pub fn solve<T: Signed, V: Copy + Into<Rational<T>>>(a: &[Vec<V>], b: &[V]) -> Solution<T> {
+    assert_eq!(a.len(), b.len(), "every equation needs a right-hand side");
+    let unknowns = a.first().map_or(0, Vec::len);
+    assert!(a.iter().all(|row| row.len() == unknowns), "every equation needs the same number of unknowns");
+
+    let mut rows: Vec<Vec<Rational<T>>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, b)| row.iter().chain([b]).map(|value| (*value).into()).collect())
+        .collect();
+
+    // reduced row echelon form: every pivot is 1 and the only non-zero value in its column
+    let mut pivots = Vec::new();
+    for column in 0..unknowns {
+        let Some(pivot) = (pivots.len()..rows.len()).find(|&r| rows[r][column] != Rational::ZERO) else {
+            continue;
+        };
+        rows.swap(pivots.len(), pivot);
+        let pivot_row = pivots.len();
+
+        let scale = rows[pivot_row][column];
+        let pivot_values: Vec<Rational<T>> = rows[pivot_row].iter().map(|value| *value / scale).collect();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[column];
+            if r == pivot_row {
+                row.clone_from(&pivot_values);
+            } else if factor != Rational::ZERO {
+                for (value, pivot_value) in row.iter_mut().zip(&pivot_values).skip(column) {
+                    *value = *value - factor * *pivot_value;
+                }
+            }
+        }
+        pivots.push(column);
+    }
+
+    if rows[pivots.len()..].iter().any(|row| row[unknowns] != Rational::ZERO) {
+        return Solution::None;
+    }
+
+    let mut particular = vec![Rational::ZERO; unknowns];
+    for (row, &column) in pivots.iter().enumerate() {
+        particular[column] = rows[row][unknowns];
+    }
+    if pivots.len() == unknowns {
+        return Solution::Unique(particular);
+    }
+
+    let free = (0..unknowns)
+        .filter(|column| !pivots.contains(column))
+        .map(|free_column| {
+            let mut direction = vec![Rational::ZERO; unknowns];
+            direction[free_column] = Rational::ONE;
+            for (row, &column) in pivots.iter().enumerate() {
+                direction[column] = -rows[row][free_column];
+            }
+            direction
+        })
+        .collect();
+    Solution::Infinite { particular, free }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/** where two lines `p1 + t * d1` and `p2 + s * d2` meet */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Intersection<T, const N: usize> {
+    Point { point: [Rational<T>; N], t: Rational<T>, s: Rational<T> },
+    Parallel,
+    Same,
+    /** lines in 3D that are neither parallel nor meet */
+    Skew,
+}
+
+/** intersects two lines in 2D or 3D, given as a point and a direction each */
+pub fn intersect_lines<T: Signed, const N: usize>(p1: [T; N], d1: [T; N], p2: [T; N], d2: [T; N]) -> Intersection<T, N> {
+    let a: Vec<Vec<Rational<T>>> = (0..N).map(|i| vec![d1[i].into(), -Rational::from(d2[i])]).collect();
+    let b: Vec<Rational<T>> = (0..N).map(|i| Rational::from(p2[i]) - p1[i].into()).collect();
+
+    let parallel = (0..N).all(|i| {
+        (i + 1..N).all(|j| Rational::from(d1[i]) * d2[j].into() == Rational::from(d1[j]) * d2[i].into())
+    });
+
+    match solve(&a, &b) {
+        Solution::Unique(ts) => {
+            let (t, s) = (ts[0], ts[1]);
+            let point = std::array::from_fn(|i| Rational::from(p1[i]) + t * d1[i].into());
+            Intersection::Point { point, t, s }
+        }
+        Solution::Infinite { .. } if parallel => Intersection::Same,
+        Solution::None if parallel => Intersection::Parallel,
+        Solution::Infinite { .. } | Solution::None => Intersection::Skew,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(numer: i128, denom: i128) -> Rational {
+        Rational::new(numer, denom)
+    }
+
+    #[test]
+    fn test_rational() {
+        assert_eq!(r(6, -8), r(-3, 4));
+        assert_eq!(r(1, 3) + r(1, 6), r(1, 2));
+        assert_eq!(r(2, 3) * r(9, 4) - Rational::from(1), r(1, 2));
+        assert_eq!(r(-7, 2).floor(), -4);
+        assert_eq!((r(3, 4) / r(3, 8)).to_integer(), Some(2));
+        assert!(r(-1, 3) < r(-1, 4));
+        assert_eq!(r(5, 10).to_string(), "1/2");
+        assert_eq!(Rational::new(i128::MAX, 1).checked_add(Rational::ONE), None);
+        assert_eq!(Rational::<i64>::ONE.checked_div(Rational::ZERO), None);
+        assert_eq!(Rational::checked_new(i64::MIN, i64::MIN), Some(Rational::ONE));
+        assert_eq!(Rational::checked_new(0, i64::MIN), Some(Rational::ZERO));
+        assert_eq!(Rational::checked_new(1, i64::MIN), None);
+        assert_eq!(Rational::new(i64::MIN, 3).floor(), -3074457345618258603);
+    }
+
+    #[test]
+    fn test_rational_ordering() {
+        assert!(Rational::<i64>::from(i64::MAX) > Rational::from(-1));
+        assert!(Rational::<i64>::from(i64::MIN) < Rational::new(i64::MAX, i64::MAX - 1));
+        assert!(Rational::new(i64::MAX - 2, i64::MAX - 1) < Rational::new(i64::MAX - 1, i64::MAX));
+        assert!(Rational::new(1, i64::MAX) < Rational::new(1, i64::MAX - 1));
+
+        // pairs of fractions up to the bounds of i8, against a comparison in a wider type
+        let fractions: Vec<Rational<i8>> = (-128..=127)
+            .step_by(5)
+            .chain([127])
+            .flat_map(|numer| (1..=127).step_by(9).chain([126, 127]).map(move |denom| Rational::new(numer, denom)))
+            .collect();
+        for a in &fractions {
+            for b in &fractions {
+                let (left, right) = (
+                    i32::from(a.numer()) * i32::from(b.denom()),
+                    i32::from(b.numer()) * i32::from(a.denom()),
+                );
+                let expected = left.cmp(&right);
+                assert_eq!(a.cmp(b), expected, "{a} <=> {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        // button A moves by (94, 34), button B by (22, 67), the prize is at (8400, 5400)
+        assert_eq!(
+            solve(&[vec![94, 22], vec![34, 67]], &[8400, 5400]),
+            Solution::<i128>::Unique(vec![r(80, 1), r(40, 1)])
+        );
+        assert_eq!(solve::<i64, i64>(&[vec![1, 1], vec![2, 2]], &[1, 3]), Solution::None);
+        assert_eq!(
+            solve::<i32, i32>(&[vec![1, 2, 3], vec![2, 4, 6]], &[6, 12]),
+            Solution::Infinite {
+                particular: vec![Rational::from(6), Rational::ZERO, Rational::ZERO],
+                free: vec![
+                    vec![Rational::from(-2), Rational::ONE, Rational::ZERO],
+                    vec![Rational::from(-3), Rational::ZERO, Rational::ONE],
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_intersect_lines() {
+        let Intersection::Point { point, t, s } = intersect_lines([19i128, 13], [-2, 1], [18, 19], [-1, -1]) else {
+            panic!("lines should intersect");
+        };
+        assert_eq!(point, [r(43, 3), r(46, 3)]);
+        assert_eq!((t, s), (r(7, 3), r(11, 3)));
+
+        assert_eq!(intersect_lines([0i64, 0], [1, 2], [1, 1], [2, 4]), Intersection::Parallel);
+        assert_eq!(intersect_lines([0i64, 0], [1, 2], [1, 2], [-2, -4]), Intersection::Same);
+        assert_eq!(
+            intersect_lines([0i64, 0, 0], [1, 1, 1], [1, 0, 0], [0, 1, 1]),
+            Intersection::Point { point: [Rational::ONE; 3], t: Rational::ONE, s: Rational::ONE }
+        );
+        assert_eq!(intersect_lines([0i64, 0, 0], [1, 0, 0], [0, 1, 1], [0, 1, 0]), Intersection::Skew);
+    }
+}
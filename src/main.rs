@@ -1,14 +1,190 @@
+use advent_of_code::template::cli::{self, CliError};
 use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
-#[cfg(feature = "today")]
-use std::process;
+use advent_of_code::template::commands::today;
 
 mod args {
-    use advent_of_code::template::Day;
-    use std::process;
+    use advent_of_code::template::cli::{self, Arg, CliError, Command, Matches, Shell};
+    use advent_of_code::template::config::{Config, OutputFormat};
+    use advent_of_code::template::{Day, DayFilters, DaySet, InputSource, Limits};
+    use std::{path::PathBuf, process, time::Duration};
+
+    pub const PROGRAM: &str = "cargo";
+    pub const ABOUT: &str =
+        "🎄 Advent of Code helpers: scaffold, download, solve and benchmark solutions.";
+
+    const LIMIT_ARGS: [Arg; 3] = [
+        Arg::option("--timeout", "SECS", "Kill a day after this many seconds"),
+        Arg::option(
+            "--part-timeout",
+            "SECS",
+            "Kill a day if one part exceeds this many seconds",
+        ),
+        Arg::option(
+            "--memory-limit",
+            "MIB",
+            "Kill a day if its resident memory exceeds this many MiB",
+        ),
+    ];
+
+    const FILTER_ARGS: [Arg; 3] = [
+        Arg::flag(
+            "--unsolved",
+            "Only run days without stored results for both parts",
+        ),
+        Arg::option(
+            "--slow",
+            "MS",
+            "Only run days whose stored run time exceeds this many ms",
+        ),
+        Arg::flag(
+            "--changed",
+            "Only run days whose solution or input changed since the last `--store`",
+        ),
+    ];
+
+    const DAYS_ARG: Arg = Arg::positional("DAYS", "Day selection, e.g. `1-10,!5`");
+
+    const YEAR_ARG: Arg = Arg::option("--year", "YEAR", "Override the puzzle year");
+    const SESSION_ARG: Arg = Arg::option(
+        "--session-file",
+        "PATH",
+        "Read the session cookie from this file",
+    );
+    const PLAIN_ARG: Arg = Arg::flag(
+        "--plain",
+        "Print output without styles or progress indicators",
+    );
+
+    const ALL_ARGS: [Arg; 9] = [
+        DAYS_ARG,
+        Arg::flag("--release", "Run with an optimized build"),
+        PLAIN_ARG,
+        FILTER_ARGS[0],
+        FILTER_ARGS[1],
+        FILTER_ARGS[2],
+        LIMIT_ARGS[0],
+        LIMIT_ARGS[1],
+        LIMIT_ARGS[2],
+    ];
+
+    const TIME_ARGS: [Arg; 13] = [
+        DAYS_ARG,
+        Arg::flag("--all", "Bench all days, including already benched ones"),
+        Arg::flag(
+            "--store",
+            "Store timings in the readme and `data/timings.json`",
+        ),
+        Arg::flag("--memory", "Collect heap statistics with dhat"),
+        Arg::flag("--perf", "Read hardware performance counters (Linux)"),
+        Arg::option(
+            "--bench-target",
+            "MS",
+            "Bench each part for approximately this many ms",
+        ),
+        PLAIN_ARG,
+        FILTER_ARGS[0],
+        FILTER_ARGS[1],
+        FILTER_ARGS[2],
+        LIMIT_ARGS[0],
+        LIMIT_ARGS[1],
+        LIMIT_ARGS[2],
+    ];
+
+    /// All commands supported by the CLI.
+    pub const COMMANDS: &[Command] = &[
+        Command::new(
+            "scaffold",
+            "Create a solution file for a day.",
+            &[
+                Arg::required("DAY", "The day to scaffold"),
+                Arg::flag(
+                    "--download",
+                    "Download input and puzzle description afterwards",
+                ),
+                Arg::flag("--overwrite", "Overwrite an existing solution file"),
+                YEAR_ARG,
+                SESSION_ARG,
+            ],
+        ),
+        Command::new(
+            "download",
+            "Download the input and puzzle description for a day.",
+            &[
+                Arg::required("DAY", "The day to download"),
+                YEAR_ARG,
+                SESSION_ARG,
+            ],
+        ),
+        Command::new(
+            "read",
+            "Read the puzzle description for a day in the terminal.",
+            &[
+                Arg::required("DAY", "The day to read"),
+                YEAR_ARG,
+                SESSION_ARG,
+            ],
+        ),
+        Command::new(
+            "solve",
+            "Run the solution for a day.",
+            &[
+                Arg::required("DAY", "The day to solve"),
+                Arg::flag("--release", "Run with an optimized build"),
+                Arg::flag("--dhat", "Profile heap allocations with dhat")
+                    .conflicts_with(&["--release"]),
+                Arg::option("--submit", "PART", "Submit the answer for part 1 or 2")
+                    .conflicts_with(&["--input", "--example"]),
+                Arg::option(
+                    "--input",
+                    "PATH",
+                    "Run against this file instead of the puzzle input, `-` reads stdin",
+                )
+                .conflicts_with(&["--example"]),
+                Arg::optional_value(
+                    "--example",
+                    "N",
+                    "Run against the example, or the numbered example `NN-N.txt`",
+                ),
+                Arg::option(
+                    "--inputs",
+                    "DIR",
+                    "Compare answers and timings for every input file in a directory",
+                )
+                .conflicts_with(&["--input", "--example", "--submit", "--dhat"]),
+                Arg::flag("--time", "Bench both parts with an optimized build")
+                    .conflicts_with(&["--dhat", "--inputs"]),
+                YEAR_ARG,
+                SESSION_ARG,
+                PLAIN_ARG,
+            ],
+        ),
+        Command::new(
+            "all",
+            "Run the solutions for all (or selected) days.",
+            &ALL_ARGS,
+        ),
+        Command::new("time", "Benchmark solutions.", &TIME_ARGS),
+        #[cfg(feature = "today")]
+        Command::new(
+            "today",
+            "Scaffold, download and read the current day.",
+            &[
+                Arg::flag(
+                    "--wait",
+                    "Scaffold the next day, wait for it to unlock and download it",
+                ),
+                SESSION_ARG,
+            ],
+        ),
+        Command::new(
+            "completions",
+            "Print a shell completion script.",
+            &[Arg::required("SHELL", "One of `bash`, `zsh` or `fish`")],
+        ),
+    ];
 
     pub enum AppArguments {
         Download {
@@ -27,83 +203,171 @@ mod args {
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            input: InputSource,
+            time: bool,
         },
         All {
+            days: Option<DaySet>,
+            filters: DayFilters,
             release: bool,
+            limits: Limits,
         },
         Time {
             all: bool,
-            day: Option<Day>,
+            days: Option<DaySet>,
+            filters: DayFilters,
             store: bool,
+            memory: bool,
+            perf: bool,
+            limits: Limits,
+        },
+        Completions {
+            shell: Shell,
         },
         #[cfg(feature = "today")]
-        Today,
+        Today {
+            wait: bool,
+        },
+    }
+
+    fn parse_limits(matches: &Matches) -> Result<Limits, CliError> {
+        Ok(Limits {
+            day_timeout: matches.value::<u64>("--timeout")?.map(Duration::from_secs),
+            part_timeout: matches
+                .value::<u64>("--part-timeout")?
+                .map(Duration::from_secs),
+            memory_limit: matches
+                .value::<u64>("--memory-limit")?
+                .map(|mib| mib * 1024 * 1024),
+        })
+    }
+
+    fn parse_filters(matches: &Matches) -> Result<DayFilters, CliError> {
+        Ok(DayFilters {
+            unsolved: matches.flag("--unsolved"),
+            slow_millis: matches.value("--slow")?,
+            changed: matches.flag("--changed"),
+        })
+    }
+
+    fn parse_input(matches: &Matches) -> Result<InputSource, CliError> {
+        if let Some(dir) = matches.value("--inputs")? {
+            return Ok(InputSource::Directory(dir));
+        }
+        if let Some(path) = matches.value("--input")? {
+            return Ok(InputSource::File(path));
+        }
+        if matches.flag("--example") {
+            return Ok(InputSource::Example(matches.value("--example")?));
+        }
+        Ok(InputSource::Puzzle)
     }
 
-    pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
-        let mut args = pico_args::Arguments::from_env();
+    /// Loads `aoc.toml` and applies command-line overrides to it.
+    fn configure(matches: &Matches) -> Result<Config, CliError> {
+        let mut config = Config::load().unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        });
 
-        let app_args = match args.subcommand()?.as_deref() {
-            Some("all") => AppArguments::All {
-                release: args.contains("--release"),
+        if let Some(year) = matches.value("--year")? {
+            config.year = Some(year);
+        }
+        if let Some(file) = matches.value::<PathBuf>("--session-file")? {
+            config.session.file = Some(file);
+        }
+        if let Some(target_ms) = matches.value("--bench-target")? {
+            config.bench.target_ms = target_ms;
+        }
+        if matches.flag("--plain") {
+            config.output.format = OutputFormat::Plain;
+        }
+
+        Ok(config)
+    }
+
+    pub fn parse() -> Result<AppArguments, CliError> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let matches = cli::parse(PROGRAM, ABOUT, COMMANDS, &args)?;
+        Config::set(configure(&matches)?);
+
+        let app_args = match matches.command {
+            "all" => AppArguments::All {
+                days: matches.value("DAYS")?,
+                filters: parse_filters(&matches)?,
+                release: matches.flag("--release"),
+                limits: parse_limits(&matches)?,
             },
-            Some("time") => {
-                let all = args.contains("--all");
-                let store = args.contains("--store");
-
-                AppArguments::Time {
-                    all,
-                    day: args.opt_free_from_str()?,
-                    store,
-                }
-            }
-            Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
+            "time" => AppArguments::Time {
+                all: matches.flag("--all"),
+                days: matches.value("DAYS")?,
+                filters: parse_filters(&matches)?,
+                store: matches.flag("--store"),
+                memory: matches.flag("--memory"),
+                perf: matches.flag("--perf"),
+                limits: parse_limits(&matches)?,
+            },
+            "download" => AppArguments::Download {
+                day: matches.required("DAY")?,
             },
-            Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
+            "read" => AppArguments::Read {
+                day: matches.required("DAY")?,
             },
-            Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
-                download: args.contains("--download"),
-                overwrite: args.contains("--overwrite"),
+            "scaffold" => AppArguments::Scaffold {
+                day: matches.required("DAY")?,
+                download: matches.flag("--download"),
+                overwrite: matches.flag("--overwrite"),
             },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
+            "solve" => AppArguments::Solve {
+                day: matches.required("DAY")?,
+                release: matches.flag("--release"),
+                dhat: matches.flag("--dhat"),
+                submit: matches.value("--submit")?,
+                input: parse_input(&matches)?,
+                time: matches.flag("--time"),
+            },
+            "completions" => AppArguments::Completions {
+                shell: matches.required("SHELL")?,
             },
             #[cfg(feature = "today")]
-            Some("today") => AppArguments::Today,
-            Some(x) => {
-                eprintln!("Unknown command: {x}");
-                process::exit(1);
-            }
-            None => {
-                eprintln!("No command specified.");
-                process::exit(1);
-            }
+            "today" => AppArguments::Today {
+                wait: matches.flag("--wait"),
+            },
+            _ => unreachable!(),
         };
 
-        let remaining = args.finish();
-        if !remaining.is_empty() {
-            eprintln!("Warning: unknown argument(s): {remaining:?}.");
-        }
-
         Ok(app_args)
     }
 }
 
 fn main() {
     match parse() {
-        Err(err) => {
+        Err(CliError::Help(help)) => {
+            println!("{help}");
+        }
+        Err(CliError::Usage(err)) => {
             eprintln!("Error: {err}");
-            std::process::exit(1);
+            std::process::exit(2);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
+            AppArguments::All {
+                days,
+                filters,
+                release,
+                limits,
+            } => all::handle(days, filters, release, limits),
+            AppArguments::Time {
+                days,
+                filters,
+                all,
+                store,
+                memory,
+                perf,
+                limits,
+            } => time::handle(days, all, filters, store, memory, perf, limits),
+            AppArguments::Completions { shell } => {
+                print!("{}", cli::completions(shell, "aoc", args::COMMANDS));
+            }
             AppArguments::Download { day } => download::handle(day),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold {
@@ -121,24 +385,11 @@ fn main() {
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                input,
+                time,
+            } => solve::handle(day, release, dhat, submit, &input, time),
             #[cfg(feature = "today")]
-            AppArguments::Today => {
-                match Day::today() {
-                    Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
-                        read::handle(day)
-                    }
-                    None => {
-                        eprintln!(
-                            "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
-                        );
-                        process::exit(1)
-                    }
-                };
-            }
+            AppArguments::Today { wait } => today::handle(wait),
         },
     };
 }
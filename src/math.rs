@@ -0,0 +1,525 @@
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+/** the primitive integers, signed and unsigned, from 8 to 128 bits */
+pub trait Integer:
+    Copy
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + Default
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn wrapping_add(self, other: Self) -> Self;
+    fn wrapping_sub(self, other: Self) -> Self;
+    /** the absolute value, or None if it does not fit, i.e. for `MIN` of a signed type */
+    fn checked_abs(self) -> Option<Self>;
+    /** the remainder in `0..m` for a positive `m` */
+    fn rem_euclid(self, m: Self) -> Self;
+    /** the largest integer whose square is at most `self`; panics for negative numbers */
+    fn isqrt(self) -> Self;
+    fn is_negative(self) -> bool;
+    fn to_u128(self) -> Option<u128>;
+}
+
+macro_rules! impl_integer {
+    ($abs:ident, $($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const TWO: Self = 2;
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn wrapping_add(self, other: Self) -> Self {
+                <$t>::wrapping_add(self, other)
+            }
+
+            fn wrapping_sub(self, other: Self) -> Self {
+                <$t>::wrapping_sub(self, other)
+            }
+
+            fn checked_abs(self) -> Option<Self> {
+                impl_integer!(@abs $abs, self)
+            }
+
+            fn rem_euclid(self, m: Self) -> Self {
+                <$t>::rem_euclid(self, m)
+            }
+
+            fn isqrt(self) -> Self {
+                <$t>::isqrt(self)
+            }
+
+            fn is_negative(self) -> bool {
+                impl_integer!(@negative $abs, self)
+            }
+
+            fn to_u128(self) -> Option<u128> {
+                u128::try_from(self).ok()
+            }
+        }
+    )*};
+    (@abs signed, $value:expr) => { $value.checked_abs() };
+    (@abs unsigned, $value:expr) => { Some($value) };
+    (@negative signed, $value:expr) => { $value < 0 };
+    (@negative unsigned, $value:expr) => { false };
+}
+
+/** the signed primitive integers */
+pub trait Signed: Integer {}
+
+impl_integer!(signed, i8, i16, i32, i64, i128, isize);
+impl_integer!(unsigned, u8, u16, u32, u64, u128, usize);
+
+impl Signed for i8 {}
+impl Signed for i16 {}
+impl Signed for i32 {}
+impl Signed for i64 {}
+impl Signed for i128 {}
+impl Signed for isize {}
+
+/** the greatest common divisor, which is never negative; panics if it does not fit, e.g. for `gcd(i64::MIN, 0)` */
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("gcd does not fit into the integer type")
+}
+
+/** the greatest common divisor, or None if it does not fit, which only happens for multiples of `MIN` */
+pub fn checked_gcd<T: Integer>(a: T, b: T) -> Option<T> {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        (a, b) = (b, checked_rem(a, b));
+    }
+    a.checked_abs()
+}
+
+/** `a % b` for a non-zero `b`, which is zero for `MIN % -1` instead of overflowing */
+fn checked_rem<T: Integer>(a: T, b: T) -> T {
+    if b.checked_abs() == Some(T::ONE) {
+        T::ZERO
+    } else {
+        a % b
+    }
+}
+
+pub fn gcd_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::ZERO, gcd)
+}
+
+/** the least common multiple, which is never negative, or None if it overflows */
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / checked_gcd(a, b)?).checked_mul(b)?.checked_abs()
+}
+
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().try_fold(T::ONE, lcm)
+}
+
+/** returns `(g, x, y)` with `a * x + b * y = g`, where `g` is the greatest common divisor,
+or None if a value does not fit, e.g. for `extended_gcd(i64::MIN, 0)` */
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> Option<(T, T, T)> {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+    while r != T::ZERO {
+        let remainder = checked_rem(old_r, r);
+        if remainder == T::ZERO {
+            // the coefficients of the last step are not needed, so `MIN / -1` is never computed
+            (old_r, old_x, old_y) = (r, x, y);
+            break;
+        }
+        let q = old_r / r;
+        (old_r, r) = (r, remainder);
+        (old_x, x) = (x, old_x.checked_sub(q.checked_mul(x)?)?);
+        (old_y, y) = (y, old_y.checked_sub(q.checked_mul(y)?)?);
+    }
+    if old_r.is_negative() {
+        Some((T::ZERO.checked_sub(old_r)?, T::ZERO.checked_sub(old_x)?, T::ZERO.checked_sub(old_y)?))
+    } else {
+        Some((old_r, old_x, old_y))
+    }
+}
+
+/** `(a + b) % m` for `a` and `b` in `0..m`, without overflowing */
+pub fn add_mod<T: Integer>(a: T, b: T, m: T) -> T {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/** `(a - b) % m` for `a` and `b` in `0..m`, without overflowing */
+pub fn sub_mod<T: Integer>(a: T, b: T, m: T) -> T {
+    if a >= b {
+        a - b
+    } else {
+        m - (b - a)
+    }
+}
+
+/** `(a * b) % m` for a positive `m`, without overflowing */
+pub fn mul_mod<T: Integer>(a: T, b: T, m: T) -> T {
+    let (mut a, mut b) = (a.rem_euclid(m), b.rem_euclid(m));
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    // double and add when the product does not fit
+    let mut result = T::ZERO;
+    while b != T::ZERO {
+        if b % T::TWO == T::ONE {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b = b / T::TWO;
+    }
+    result
+}
+
+/** `base ^ exp % m` for a positive `m` */
+pub fn mod_pow<T: Integer>(base: T, exp: u128, m: T) -> T {
+    let (mut base, mut exp) = (base.rem_euclid(m), exp);
+    let mut result = T::ONE % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/** the `x` in `0..m` with `a * x % m == 1`, or None if `a` and `m` are not coprime */
+pub fn mod_inverse<T: Integer>(a: T, m: T) -> Option<T> {
+    // extended Euclid, keeping the coefficient in 0..m so it works for unsigned types
+    let (mut old_r, mut r) = (a.rem_euclid(m), m);
+    let (mut old_x, mut x) = (T::ONE % m, T::ZERO);
+    while r != T::ZERO {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, sub_mod(old_x, mul_mod(q, x, m), m));
+    }
+    (old_r == T::ONE).then_some(old_x)
+}
+
+/** solves `x % m == a` for every `(a, m)`, returning `(x, lcm of all m)` with `x` in `0..lcm`;
+the moduli do not have to be coprime. Returns None if there is no solution or the lcm overflows */
+pub fn crt<T: Integer>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let mut result = (T::ZERO, T::ONE);
+    for &(a, m) in congruences {
+        let (x, l) = result;
+        let a = a.rem_euclid(m);
+        let g = gcd(l, m);
+
+        let difference = sub_mod(a, x % m, m);
+        if difference % g != T::ZERO {
+            return None;
+        }
+        let lcm = (l / g).checked_mul(m)?;
+        let reduced = m / g;
+        let t = mul_mod(difference / g, mod_inverse(l / g, reduced)?, reduced);
+
+        // x < l and t < m / g, so this is below the lcm and can not overflow
+        result = (x + l * t, lcm);
+    }
+    Some(result)
+}
+
+/** all primes up to and including `limit`, using the sieve of Eratosthenes */
+pub fn primes(limit: usize) -> Vec<usize> {
+    let mut composite = vec![false; limit + 1];
+    let mut primes = Vec::new();
+    for n in 2..=limit {
+        if !composite[n] {
+            primes.push(n);
+            for multiple in (n * n..=limit).step_by(n) {
+                composite[multiple] = true;
+            }
+        }
+    }
+    primes
+}
+
+/** Miller-Rabin with the first 13 prime bases, which is exact for every number below 3.3 * 10^24. Larger numbers
+also have to pass a strong Lucas test, which makes it a Baillie-PSW test; no composite passing it is known */
+pub fn is_prime<T: Integer>(n: T) -> bool {
+    let Some(n) = n.to_u128() else {
+        return false;
+    };
+    const BASES: [u128; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+    const EXACT_BELOW: u128 = 3_317_044_064_679_887_385_961_981;
+    if n < 2 {
+        return false;
+    }
+    if let Some(&base) = BASES.iter().find(|&&base| n % base == 0) {
+        return n == base;
+    }
+
+    let (mut d, mut s) = (n - 1, 0);
+    while d % 2 == 0 {
+        (d, s) = (d / 2, s + 1);
+    }
+    let is_probable_prime = BASES.iter().all(|&base| {
+        let mut x = mod_pow(base, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        (1..s).any(|_| {
+            x = mul_mod(x, x, n);
+            x == n - 1
+        })
+    });
+    is_probable_prime && (n < EXACT_BELOW || is_strong_lucas_probable_prime(n))
+}
+
+/** the strong Lucas test with Selfridge's parameters, for an odd `n` without small factors */
+fn is_strong_lucas_probable_prime(n: u128) -> bool {
+    // there is no suitable D for squares
+    if n.isqrt() * n.isqrt() == n {
+        return false;
+    }
+
+    // the first D of 5, -7, 9, -11, ... with a Jacobi symbol (D / n) of -1
+    let mut d = 5i128;
+    loop {
+        match jacobi(residue(d, n), n) {
+            -1 => break,
+            0 if d.unsigned_abs() != n => return false,
+            _ => d = if d > 0 { -d - 2 } else { -d + 2 },
+        }
+    }
+    let (p, q) = (1, (1 - d) / 4);
+    let (d, p, q) = (residue(d, n), residue(p, n), residue(q, n));
+
+    // n + 1 = k * 2^s with an odd k; n is below `u128::MAX` as that has small factors
+    let (mut k, mut s) = (n + 1, 0);
+    while k % 2 == 0 {
+        (k, s) = (k / 2, s + 1);
+    }
+
+    // (x / 2) % n for an odd n, without overflowing
+    let half = |x: u128| if x.is_multiple_of(2) { x / 2 } else { x / 2 + n / 2 + 1 };
+
+    // U_k, V_k and Q^k, by doubling along the bits of k
+    let (mut u, mut v, mut q_k) = (1, p, q);
+    for bit in (0..k.ilog2()).rev() {
+        u = mul_mod(u, v, n);
+        v = sub_mod(mul_mod(v, v, n), add_mod(q_k, q_k, n), n);
+        q_k = mul_mod(q_k, q_k, n);
+        if (k >> bit) & 1 == 1 {
+            (u, v) = (
+                half(add_mod(mul_mod(p, u, n), v, n)),
+                half(add_mod(mul_mod(d, u, n), mul_mod(p, v, n), n)),
+            );
+            q_k = mul_mod(q_k, q, n);
+        }
+    }
+
+    if u == 0 || v == 0 {
+        return true;
+    }
+    (1..s).any(|_| {
+        v = sub_mod(mul_mod(v, v, n), add_mod(q_k, q_k, n), n);
+        q_k = mul_mod(q_k, q_k, n);
+        v == 0
+    })
+}
+
+/** `value % n` in `0..n` for a small signed value */
+fn residue(value: i128, n: u128) -> u128 {
+    let remainder = value.unsigned_abs() % n;
+    if value < 0 && remainder != 0 {
+        n - remainder
+    } else {
+        remainder
+    }
+}
+
+/** the Jacobi symbol `(a / n)` for an odd `n` */
+fn jacobi(a: u128, n: u128) -> i8 {
+    let (mut a, mut n) = (a % n, n);
+    let mut result = 1;
+    while a != 0 {
+        while a % 2 == 0 {
+            a /= 2;
+            if n % 8 == 3 || n % 8 == 5 {
+                result = -result;
+            }
+        }
+        (a, n) = (n, a);
+        if a % 4 == 3 && n % 4 == 3 {
+            result = -result;
+        }
+        a %= n;
+    }
+    if n == 1 {
+        result
+    } else {
+        0
+    }
+}
+
+/** the prime factors of a positive number with their exponents, in ascending order, using trial division */
+pub fn factorize<T: Integer>(n: T) -> Vec<(T, u32)> {
+    let mut factors = Vec::new();
+    let mut n = n;
+    let mut p = T::TWO;
+    while p.checked_mul(p).is_some_and(|square| square <= n) {
+        let mut exponent = 0;
+        while n % p == T::ZERO {
+            n = n / p;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((p, exponent));
+        }
+        p = if p == T::TWO { p + T::ONE } else { p + T::TWO };
+    }
+    if n > T::ONE {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+/** all positive divisors of a positive number, in ascending order */
+pub fn divisors<T: Integer>(n: T) -> Vec<T> {
+    let mut divisors = vec![T::ONE];
+    for (p, exponent) in factorize(n) {
+        let mut powers = Vec::new();
+        for d in &divisors {
+            let mut power = *d;
+            for _ in 0..exponent {
+                power = power * p;
+                powers.push(power);
+            }
+        }
+        divisors.extend(powers);
+    }
+    divisors.sort();
+    divisors
+}
+
+/** the largest integer whose square is at most `n`; panics for negative numbers */
+pub fn isqrt<T: Integer>(n: T) -> T {
+    n.isqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, -18), 6);
+        assert_eq!(gcd(i64::MIN, -1), 1);
+        assert_eq!(gcd(-1, i64::MIN), 1);
+        assert_eq!(gcd(i8::MIN, 6), 2);
+        assert_eq!(checked_gcd(i8::MIN, i8::MIN), None);
+        assert_eq!(gcd_all([24u8, 36, 60]), 12);
+        assert_eq!(lcm(-4i32, 6), Some(12));
+        assert_eq!(lcm_all([2u64, 3, 4, 5]), Some(60));
+        assert_eq!(lcm(u128::MAX, 2), None);
+        assert_eq!(lcm_all([i128::MAX, 3]), None);
+        assert_eq!(lcm(i64::MIN, i64::MIN), None);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240i64, 46), (-7, 3), (0, 5), (17, -17), (i64::MIN, -1), (-1, i64::MIN), (i64::MIN, 3)] {
+            let (g, x, y) = extended_gcd(a, b).unwrap();
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(i128::from(a) * i128::from(x) + i128::from(b) * i128::from(y), i128::from(g));
+        }
+        assert_eq!(extended_gcd(i64::MIN, 0), None);
+        assert_eq!(extended_gcd(i8::MIN, i8::MIN), None);
+    }
+
+    #[test]
+    fn test_modular_arithmetic() {
+        assert_eq!(mod_pow(2u32, 10, 1000), 24);
+        assert_eq!(mod_pow(-3i64, 3, 7), 1);
+        assert_eq!(mod_pow(u128::MAX - 1, u128::MAX, u128::MAX), u128::MAX - 1);
+        assert_eq!(mul_mod(u64::MAX - 1, u64::MAX - 2, u64::MAX), 2);
+        assert_eq!(mod_inverse(3u8, 11), Some(4));
+        assert_eq!(mod_inverse(6i32, 9), None);
+        assert_eq!(mod_inverse(u128::MAX - 1, u128::MAX), Some(u128::MAX - 1));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(0u64, 7), (12, 13), (55, 59), (25, 31), (12, 19)]), Some((1068781, 3162341)));
+        assert_eq!(crt(&[(1u32, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1u32, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(-1i32, 10)]), Some((9, 10)));
+        assert_eq!(crt(&[(0u8, 200), (1, 199)]), None);
+    }
+
+    #[test]
+    fn test_strong_lucas() {
+        let primes = primes(20_000);
+        let pseudoprimes: Vec<u128> = (45..20_000u128)
+            .step_by(2)
+            .filter(|&n| !primes.contains(&(n as usize)) && is_strong_lucas_probable_prime(n))
+            .collect();
+        assert_eq!(pseudoprimes, vec![5459, 5777, 10877, 16109, 18971]);
+        assert!(primes.iter().filter(|&&p| p > 41).all(|&p| is_strong_lucas_probable_prime(p as u128)));
+        assert_eq!(jacobi(1001, 9907), -1);
+        assert_eq!(jacobi(19, 45), 1);
+        assert_eq!(jacobi(8, 21), -1);
+        assert_eq!(jacobi(5, 21), 1);
+        assert_eq!(jacobi(3, 21), 0);
+    }
+
+    #[test]
+    fn test_primes() {
+        assert_eq!(primes(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert!(is_prime(1_000_000_007u64));
+        assert!(!is_prime(3_215_031_751u64));
+        assert!(is_prime(u64::MAX as u128 - 58));
+        assert!(!is_prime(-7i32));
+        // a strong pseudoprime to the first 12 prime bases
+        assert!(!is_prime(318_665_857_834_031_151_167_461u128));
+        assert!(is_prime(2u128.pow(89) - 1));
+        assert!(is_prime(2u128.pow(127) - 1));
+        assert!(!is_prime((2u128.pow(61) - 1) * (2u128.pow(61) - 1)));
+        assert!(!is_prime((2u128.pow(89) - 1) * 43));
+        assert_eq!(factorize(360u32), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize(600_851_475_143i64), vec![(71, 1), (839, 1), (1471, 1), (6857, 1)]);
+        assert_eq!(divisors(28u16), vec![1, 2, 4, 7, 14, 28]);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(isqrt(99i8), 9);
+    }
+}
@@ -0,0 +1,254 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+thread_local! {
+    // clears the tables of `memoize!` functions that were called on this thread
+    static TABLES: RefCell<Vec<fn()>> = const { RefCell::new(Vec::new()) };
+    // stats of tables that were cleared or dropped since the last `take_stats`
+    static STATS: RefCell<Vec<(&'static str, MemoStats)>> = const { RefCell::new(Vec::new()) };
+    // whether cleared or dropped tables report their stats, see `without_stats`
+    static RECORDING: Cell<bool> = const { Cell::new(true) };
+}
+
+/** how often a memo table found a value, and how often it had to compute one */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/** a cache for a recursive function, keyed by its arguments */
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+    name: &'static str,
+    values: HashMap<K, V>,
+    stats: MemoStats,
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    /** the name labels the stats that the runner prints in debug builds */
+    pub fn new(name: &'static str) -> Self {
+        Self { name, values: HashMap::new(), stats: MemoStats::default() }
+    }
+
+    /** the cached value of `key`, or the result of `f`, which receives the memo so it can recurse */
+    pub fn cached(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = f(self);
+        self.insert(key, value.clone());
+        value
+    }
+
+    /** looks up a value, counting a hit or a miss */
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let value = self.values.get(key).cloned();
+        match value {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        value
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.values.insert(key, value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /** the hits and misses since the table was created or last cleared */
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    /** forgets every value, e.g. between parts whose recursions differ */
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.report();
+    }
+}
+
+impl<K, V> Memo<K, V> {
+    fn report(&mut self) {
+        let stats = std::mem::take(&mut self.stats);
+        if stats != MemoStats::default() && RECORDING.try_with(Cell::get).unwrap_or(false) {
+            // `try_with` because the stats may already be gone when a thread exits
+            let _ = STATS.try_with(|all| all.borrow_mut().push((self.name, stats)));
+        }
+    }
+}
+
+impl<K, V> Drop for Memo<K, V> {
+    fn drop(&mut self) {
+        self.report();
+    }
+}
+
+/** clears the tables of every `memoize!` function on this thread; the runner does this before every part */
+pub fn clear_all() {
+    let tables = TABLES.with(|tables| tables.borrow().clone());
+    for clear in tables {
+        clear();
+    }
+}
+
+/** the stats of tables that were cleared or dropped since the last call, summed up by name */
+pub fn take_stats() -> Vec<(&'static str, MemoStats)> {
+    let mut summed: Vec<(&'static str, MemoStats)> = Vec::new();
+    for (name, stats) in STATS.with(|all| std::mem::take(&mut *all.borrow_mut())) {
+        match summed.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, sum)) => {
+                sum.hits += stats.hits;
+                sum.misses += stats.misses;
+            }
+            None => summed.push((name, stats)),
+        }
+    }
+    summed
+}
+
+/** resets whether stats are recorded, also when the function run by `without_stats` unwinds */
+struct RestoreRecording(bool);
+
+impl Drop for RestoreRecording {
+    fn drop(&mut self) {
+        RECORDING.with(|recording| recording.set(self.0));
+    }
+}
+
+/** runs `f` without keeping the stats of tables that are cleared or dropped meanwhile, e.g. while benching */
+pub fn without_stats<T>(f: impl FnOnce() -> T) -> T {
+    let _restore = RestoreRecording(RECORDING.with(|recording| recording.replace(false)));
+    f()
+}
+
+#[doc(hidden)]
+pub fn register(clear: fn()) {
+    TABLES.with(|tables| tables.borrow_mut().push(clear));
+}
+
+/** turns a function into one that caches its results by its arguments, which must be `Clone + Hash + Eq + 'static`;
+recursive calls go through the cache, and the runner clears it between parts:
+
+```
+advent_of_code::memoize! {
+    fn stones(stone: u64, blinks: u32) -> u64 {
+        let digits = stone.checked_ilog10().unwrap_or(0) + 1;
+        match blinks {
+            0 => 1,
+            _ if stone == 0 => stones(1, blinks - 1),
+            _ if digits % 2 == 0 => {
+                let half = 10u64.pow(digits / 2);
+                stones(stone / half, blinks - 1) + stones(stone % half, blinks - 1)
+            }
+            _ => stones(stone * 2024, blinks - 1),
+        }
+    }
+}
+
+assert_eq!(stones(125, 25) + stones(17, 25), 55312);
+```
+
+functions that borrow context such as a list of patterns use a `Memo` directly */
+#[macro_export]
+macro_rules! memoize {
+    ($(#[$attr:meta])* $vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty $body:block) => {
+        $(#[$attr])*
+        $vis fn $name($($arg: $ty),*) -> $ret {
+            fn uncached($($arg: $ty),*) -> $ret $body
+
+            thread_local! {
+                static MEMO: ::std::cell::RefCell<$crate::memo::Memo<($($ty,)*), $ret>> = {
+                    $crate::memo::register(|| MEMO.with(|memo| memo.borrow_mut().clear()));
+                    ::std::cell::RefCell::new($crate::memo::Memo::new(stringify!($name)))
+                };
+            }
+
+            let key = ($(::std::clone::Clone::clone(&$arg),)*);
+            if let Some(value) = MEMO.with(|memo| memo.borrow_mut().get(&key)) {
+                return value;
+            }
+            let value = uncached($($arg),*);
+            MEMO.with(|memo| memo.borrow_mut().insert(key, ::std::clone::Clone::clone(&value)));
+            value
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::memoize! {
+        fn fibonacci(n: u64) -> u64 {
+            if n < 2 { n } else { fibonacci(n - 1) + fibonacci(n - 2) }
+        }
+    }
+
+    #[test]
+    fn test_memoize() {
+        clear_all();
+        take_stats();
+        assert_eq!(fibonacci(90), 2880067194370816120);
+        assert_eq!(fibonacci(80), 23416728348467685);
+
+        clear_all();
+        assert_eq!(take_stats(), vec![("fibonacci", MemoStats { hits: 89, misses: 91 })]);
+        assert_eq!(fibonacci(10), 55);
+        clear_all();
+        assert_eq!(take_stats(), vec![("fibonacci", MemoStats { hits: 8, misses: 11 })]);
+    }
+
+    fn arrangements<'a>(memo: &mut Memo<&'a str, u64>, patterns: &[&str], design: &'a str) -> u64 {
+        if design.is_empty() {
+            return 1;
+        }
+        memo.cached(design, |memo| {
+            patterns.iter().filter_map(|p| design.strip_prefix(p)).map(|rest| arrangements(memo, patterns, rest)).sum()
+        })
+    }
+
+    #[test]
+    fn test_memo() {
+        let patterns = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
+        let mut memo = Memo::new("arrangements");
+        assert_eq!(arrangements(&mut memo, &patterns, "rrbgbr"), 6);
+        assert_eq!(memo.stats(), MemoStats { hits: 2, misses: 6 });
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(arrangements(&mut memo, &patterns, "bbrgwb"), 0);
+        drop(memo);
+        assert_eq!(take_stats(), vec![("arrangements", MemoStats { hits: 3, misses: 11 })]);
+    }
+
+    #[test]
+    fn test_without_stats() {
+        clear_all();
+        take_stats();
+        without_stats(|| {
+            assert_eq!(fibonacci(20), 6765);
+            clear_all();
+            let mut memo = Memo::new("dropped");
+            memo.cached(1, |_| 1);
+        });
+        assert_eq!(take_stats(), vec![]);
+
+        assert_eq!(fibonacci(5), 5);
+        clear_all();
+        assert_eq!(take_stats(), vec![("fibonacci", MemoStats { hits: 3, misses: 6 })]);
+
+        let panicked = std::panic::catch_unwind(|| without_stats(|| panic!("solution failed")));
+        assert!(panicked.is_err());
+        assert_eq!(fibonacci(5), 5);
+        clear_all();
+        assert_eq!(take_stats(), vec![("fibonacci", MemoStats { hits: 3, misses: 6 })]);
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt::Display;
 
 type PrintJob = Vec<u32>;
 pub struct Printer {
@@ -6,38 +7,58 @@ pub struct Printer {
     jobs: Vec<PrintJob>,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum PrinterError {
+    InvalidRule { line: usize, text: String },
+    InvalidJob { line: usize, text: String },
+}
+
+impl Display for PrinterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrinterError::InvalidRule { line, text } => {
+                write!(f, "line {line}: expected a rule like `47|53`, found `{text}`")
+            }
+            PrinterError::InvalidJob { line, text } => {
+                write!(f, "line {line}: expected a job like `75,47,61`, found `{text}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PrinterError {}
+
 impl Printer {
-    pub fn new(input: &str) -> Self {
+    /** parses the ordering rules, followed by an empty line and the print jobs */
+    pub fn new(input: &str) -> Result<Self, PrinterError> {
         let mut pages_followers: HashMap<u32, Vec<u32>> = HashMap::new();
         let mut jobs = Vec::new();
 
         let mut reading_jobs = false;
-        for line in input.lines() {
+        for (i, line) in input.lines().enumerate() {
             if line.is_empty() {
                 reading_jobs = true;
                 continue;
             }
 
             if !reading_jobs {
-                let parts: Vec<&str> = line.split("|").collect();
-                let page_id = parts[0].parse().unwrap();
-                let follower = parts[1].parse().unwrap();
-
-                if let Some(followers) = pages_followers.get_mut(&page_id) {
-                    followers.push(follower);
-                } else {
-                    pages_followers.insert(page_id, vec![follower]);
-                }
+                let invalid_rule = || PrinterError::InvalidRule { line: i + 1, text: line.into() };
+                let (page_id, follower) = line.split_once('|').ok_or_else(invalid_rule)?;
+                let page_id = page_id.parse().map_err(|_| invalid_rule())?;
+                let follower = follower.parse().map_err(|_| invalid_rule())?;
+
+                pages_followers.entry(page_id).or_default().push(follower);
             } else {
                 let job = line
-                    .split(",")
-                    .map(|s| s.parse().unwrap())
-                    .collect();
+                    .split(',')
+                    .map(|s| s.parse())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| PrinterError::InvalidJob { line: i + 1, text: line.into() })?;
                 jobs.push(job);
             }
         }
 
-        Self { pages_followers, jobs }
+        Ok(Self { pages_followers, jobs })
     }
 
     pub fn get_jobs(&self) -> &Vec<PrintJob> {
@@ -98,3 +119,31 @@ impl Printer {
         job_to_fix
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_printer_parses_rules_and_jobs() {
+        let printer = Printer::new("47|53\n97|13\n47|61\n\n75,47,61\n97,13").unwrap();
+        assert_eq!(printer.get_page_followers(47), Some(&vec![53, 61]));
+        assert_eq!(printer.get_jobs(), &vec![vec![75, 47, 61], vec![97, 13]]);
+    }
+
+    #[test]
+    fn test_printer_rejects_invalid_input() {
+        assert_eq!(
+            Printer::new("47|53\n47-61\n\n75,47").err(),
+            Some(PrinterError::InvalidRule { line: 2, text: "47-61".into() })
+        );
+        assert_eq!(
+            Printer::new("47|53|61").err(),
+            Some(PrinterError::InvalidRule { line: 1, text: "47|53|61".into() })
+        );
+        assert_eq!(
+            Printer::new("47|53\n\n75,,47").err(),
+            Some(PrinterError::InvalidJob { line: 3, text: "75,,47".into() })
+        );
+    }
+}
@@ -0,0 +1,403 @@
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
+use crate::math::Integer;
+
+/** the inclusive `start..=end` for any range of integers, or None if it is empty */
+fn bounds<T: Integer>(range: &impl RangeBounds<T>) -> Option<(T, T)> {
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start.checked_add(T::ONE)?,
+        Bound::Unbounded => T::MIN,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => *end,
+        Bound::Excluded(end) => end.checked_sub(T::ONE)?,
+        Bound::Unbounded => T::MAX,
+    };
+    (start <= end).then_some((start, end))
+}
+
+/** a set of integers, stored as sorted, disjoint and non-adjacent inclusive ranges */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    ranges: Vec<RangeInclusive<T>>,
+}
+
+impl<T: Integer> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: Integer> RangeSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /** adds a range such as `3..7` or `3..=6`, merging it with the ranges it overlaps or touches */
+    pub fn insert(&mut self, range: impl RangeBounds<T>) {
+        let Some((mut start, mut end)) = bounds(&range) else {
+            return;
+        };
+        let first = self.ranges.partition_point(|r| r.end().checked_add(T::ONE).is_some_and(|next| next < start));
+        let last = self.ranges.partition_point(|r| end.checked_add(T::ONE).is_none_or(|next| *r.start() <= next));
+        if first < last {
+            start = start.min(*self.ranges[first].start());
+            end = end.max(*self.ranges[last - 1].end());
+        }
+        self.ranges.splice(first..last, [start..=end]);
+    }
+
+    pub fn remove(&mut self, range: impl RangeBounds<T>) {
+        let Some((start, end)) = bounds(&range) else {
+            return;
+        };
+        let first = self.ranges.partition_point(|r| *r.end() < start);
+        let last = self.ranges.partition_point(|r| *r.start() <= end);
+        if first >= last {
+            return;
+        }
+        let (left, right) = (*self.ranges[first].start(), *self.ranges[last - 1].end());
+        // the neighbours of `start` and `end` exist when the kept pieces are non-empty
+        let left = (left < start).then(|| left..=start - T::ONE);
+        let right = (end < right).then(|| end + T::ONE..=right);
+        self.ranges.splice(first..last, left.into_iter().chain(right));
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| *r.end() < value);
+        self.ranges.get(i).is_some_and(|r| *r.start() <= value)
+    }
+
+    /** whether every value of the range is in the set */
+    pub fn contains_range(&self, range: impl RangeBounds<T>) -> bool {
+        let Some((start, end)) = bounds(&range) else {
+            return true;
+        };
+        let i = self.ranges.partition_point(|r| *r.end() < start);
+        self.ranges.get(i).is_some_and(|r| *r.start() <= start && end <= *r.end())
+    }
+
+    /** whether any value of the range is in the set */
+    pub fn overlaps(&self, range: impl RangeBounds<T>) -> bool {
+        let Some((start, end)) = bounds(&range) else {
+            return false;
+        };
+        let i = self.ranges.partition_point(|r| *r.end() < start);
+        self.ranges.get(i).is_some_and(|r| *r.start() <= end)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = Vec::new();
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let (start, end) = (*a.start().max(b.start()), *a.end().min(b.end()));
+            if start <= end {
+                ranges.push(start..=end);
+            }
+            if a.end() < b.end() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /** the values in `self` that are not in `other` */
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in &other.ranges {
+            difference.remove(range.clone());
+        }
+        difference
+    }
+
+    /** the number of values in the set; panics if it does not fit into `T` */
+    pub fn count(&self) -> T {
+        self.ranges.iter().fold(T::ZERO, |count, r| {
+            r.end()
+                .checked_sub(*r.start())
+                .and_then(|len| len.checked_add(T::ONE))
+                .and_then(|len| count.checked_add(len))
+                .expect("count does not fit into the integer type")
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /** the disjoint ranges of the set, in ascending order */
+    pub fn ranges(&self) -> &[RangeInclusive<T>] {
+        &self.ranges
+    }
+}
+
+impl<T: Integer, R: RangeBounds<T>> FromIterator<R> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/** a piecewise mapping that moves source ranges to destinations; values outside of every source map to themselves */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RangeMap<T> {
+    // sorted and disjoint inclusive source ranges, with the wrapping offset from source to destination, which does not
+    // overflow for signed sources that span more than half of the type
+    entries: Vec<(RangeInclusive<T>, T)>,
+}
+
+impl<T: Integer> Default for RangeMap<T> {
+    fn default() -> Self {
+        Self { entries: Vec::new() }
+    }
+}
+
+impl<T: Integer> RangeMap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /** maps `source` to the range starting at `destination`; replaces earlier mappings where they overlap */
+    pub fn insert(&mut self, source: impl RangeBounds<T>, destination: T) {
+        let Some((start, end)) = bounds(&source) else {
+            return;
+        };
+        let first = self.entries.partition_point(|(r, _)| *r.end() < start);
+        let last = self.entries.partition_point(|(r, _)| *r.start() <= end);
+
+        let mut replacement = Vec::new();
+        if first < last {
+            let (left, left_offset) = self.entries[first].clone();
+            if *left.start() < start {
+                replacement.push((*left.start()..=start - T::ONE, left_offset));
+            }
+        }
+        replacement.push((start..=end, destination.wrapping_sub(start)));
+        if first < last {
+            let (right, right_offset) = self.entries[last - 1].clone();
+            if end < *right.end() {
+                replacement.push((end + T::ONE..=*right.end(), right_offset));
+            }
+        }
+        self.entries.splice(first..last, replacement);
+    }
+
+    pub fn map(&self, value: T) -> T {
+        let i = self.entries.partition_point(|(r, _)| *r.end() < value);
+        match self.entries.get(i) {
+            Some((r, offset)) if *r.start() <= value => value.wrapping_add(*offset),
+            _ => value,
+        }
+    }
+
+    /** maps every value of a range, splitting it where it crosses the boundaries of the sources */
+    pub fn map_range(&self, range: impl RangeBounds<T>) -> RangeSet<T> {
+        let mut mapped = RangeSet::new();
+        let Some((mut start, end)) = bounds(&range) else {
+            return mapped;
+        };
+        let mut i = self.entries.partition_point(|(r, _)| *r.end() < start);
+        loop {
+            // the piece of the range that starts at `start`, up to the next source boundary
+            let piece_end = match self.entries.get(i) {
+                Some((r, offset)) if *r.start() <= start => {
+                    let piece_end = end.min(*r.end());
+                    mapped.insert(start.wrapping_add(*offset)..=piece_end.wrapping_add(*offset));
+                    i += 1;
+                    piece_end
+                }
+                Some((r, _)) if *r.start() <= end => {
+                    mapped.insert(start..*r.start());
+                    *r.start() - T::ONE
+                }
+                _ => {
+                    mapped.insert(start..=end);
+                    end
+                }
+            };
+            if piece_end == end {
+                return mapped;
+            }
+            start = piece_end + T::ONE;
+        }
+    }
+
+    pub fn map_set(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        set.ranges().iter().map(|range| self.map_range(range.clone())).fold(RangeSet::new(), |all, mapped| all.union(&mapped))
+    }
+}
+
+impl<T: Integer, R: RangeBounds<T>> FromIterator<(R, T)> for RangeMap<T> {
+    fn from_iter<I: IntoIterator<Item = (R, T)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (source, destination) in iter {
+            map.insert(source, destination);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::Range;
+    use crate::template::testing::Rng;
+    use std::collections::BTreeSet;
+
+    fn random_range(rng: &mut Rng) -> Range<i32> {
+        let start = rng.range(0..=60) as i32;
+        start..start + rng.range(0..=15) as i32
+    }
+
+    /** a range of at most 16 values near the bounds of `u8` */
+    fn random_edge_range(rng: &mut Rng) -> RangeInclusive<u8> {
+        let start = if rng.bool() { rng.range(0..=15) } else { rng.range(240..=255) } as u8;
+        start..=start.saturating_add(rng.range(0..=15) as u8)
+    }
+
+    fn values<T: Integer + Ord>(set: &RangeSet<T>) -> BTreeSet<T>
+    where
+        RangeInclusive<T>: Iterator<Item = T>,
+    {
+        set.ranges().iter().flat_map(RangeInclusive::clone).collect()
+    }
+
+    #[test]
+    fn test_range_set_against_brute_force() {
+        let mut rng = Rng::new(45);
+        for _ in 0..200 {
+            let (mut set, mut other) = (RangeSet::new(), RangeSet::new());
+            let (mut expected, mut expected_other) = (BTreeSet::new(), BTreeSet::new());
+            for _ in 0..8 {
+                let range = random_range(&mut rng);
+                if rng.chance(0.7) {
+                    set.insert(range.clone());
+                    expected.extend(range);
+                } else {
+                    set.remove(range.clone());
+                    expected.retain(|v| !range.contains(v));
+                }
+                let range = random_range(&mut rng);
+                other.insert(range.clone());
+                expected_other.extend(range);
+            }
+
+            assert_eq!(values(&set), expected);
+            assert_eq!(set.count() as usize, expected.len());
+            assert!(set.ranges().windows(2).all(|w| *w[0].end() + 1 < *w[1].start()));
+            assert_eq!(values(&set.union(&other)), &expected | &expected_other);
+            assert_eq!(values(&set.intersection(&other)), &expected & &expected_other);
+            assert_eq!(values(&set.difference(&other)), &expected - &expected_other);
+
+            let range = random_range(&mut rng);
+            assert_eq!(set.contains(range.start), expected.contains(&range.start));
+            assert_eq!(set.contains_range(range.clone()), range.clone().all(|v| expected.contains(&v)));
+            assert_eq!(set.overlaps(range.clone()), range.clone().any(|v| expected.contains(&v)));
+
+            // ranges that end at `MAX` or start at `MIN`
+            let (mut set, mut expected) = (RangeSet::new(), BTreeSet::new());
+            for _ in 0..8 {
+                let range = random_edge_range(&mut rng);
+                if rng.chance(0.7) {
+                    set.insert(range.clone());
+                    expected.extend(range);
+                } else {
+                    set.remove(range.clone());
+                    expected.retain(|v| !range.contains(v));
+                }
+            }
+            assert_eq!(values(&set), expected);
+            assert_eq!(set.count() as usize, expected.len());
+            assert!((0..=255).all(|v| set.contains(v) == expected.contains(&v)));
+            let range = random_edge_range(&mut rng);
+            assert_eq!(set.contains_range(range.clone()), range.clone().all(|v| expected.contains(&v)));
+            assert_eq!(set.overlaps(range.clone()), range.clone().any(|v| expected.contains(&v)));
+        }
+    }
+
+    #[test]
+    fn test_range_set_bounds() {
+        let mut set: RangeSet<u8> = [250..=255, 0..=2, 3..=3].into_iter().collect();
+        assert_eq!(set.ranges(), &[0..=3, 250..=255]);
+        assert_eq!(set.count(), 10);
+        assert!(set.contains(255));
+        assert!(set.contains_range(..4));
+        assert!(set.contains_range(252..));
+        set.remove(255..=255);
+        assert_eq!(set.ranges(), &[0..=3, 250..=254]);
+        set.insert(4..);
+        assert_eq!(set.ranges(), &[0..=255]);
+        assert!(RangeSet::<u8>::new().contains_range(5..5));
+    }
+
+    #[test]
+    fn test_range_map_against_brute_force() {
+        let mut rng = Rng::new(46);
+        for _ in 0..200 {
+            let mut map = RangeMap::new();
+            let mut expected: Vec<i32> = (0..100).collect();
+            for _ in 0..4 {
+                let source = random_range(&mut rng);
+                let destination = rng.range(0..=100) as i32;
+                map.insert(source.clone(), destination);
+                for v in source.clone() {
+                    expected[v as usize] = destination + v - source.start;
+                }
+            }
+
+            let range = random_range(&mut rng);
+            for v in range.clone() {
+                assert_eq!(map.map(v), expected[v as usize]);
+            }
+            let mapped: BTreeSet<i32> = range.clone().map(|v| expected[v as usize]).collect();
+            assert_eq!(values(&map.map_range(range.clone())), mapped);
+
+            let set: RangeSet<i32> = [range.clone(), random_range(&mut rng)].into_iter().collect();
+            let mapped: BTreeSet<i32> = values(&set).iter().map(|v| expected[*v as usize]).collect();
+            assert_eq!(values(&map.map_set(&set)), mapped);
+        }
+    }
+
+    #[test]
+    fn test_range_map_seeds() {
+        // seed-to-soil map: `50 98 2` and `52 50 48`
+        let map: RangeMap<u64> = [(98..100, 50), (50..98, 52)].into_iter().collect();
+        assert_eq!([79, 14, 55, 13].map(|seed| map.map(seed)), [81, 14, 57, 13]);
+        assert_eq!(map.map_range(45..60).ranges(), &[45..=49, 52..=61]);
+        assert_eq!(map.map_range(90..100).ranges(), &[50..=51, 92..=99]);
+    }
+
+    #[test]
+    fn test_range_map_bounds() {
+        let map: RangeMap<u8> = [(250..=255, 0), (0..=5, 250)].into_iter().collect();
+        assert_eq!([255, 250, 0, 5, 6].map(|v| map.map(v)), [5, 0, 250, 255, 6]);
+        assert_eq!(map.map_range(..).ranges(), &[0..=255]);
+        assert_eq!(map.map_range(253..).ranges(), &[3..=5]);
+
+        let identity: RangeMap<i8> = [(i8::MIN..=i8::MAX, i8::MIN)].into_iter().collect();
+        assert_eq!([i8::MIN, -1, 0, i8::MAX].map(|v| identity.map(v)), [i8::MIN, -1, 0, i8::MAX]);
+        assert_eq!(identity.map_range(-100..=120).ranges(), &[-100..=120]);
+
+        let mut shifted: RangeMap<i8> = [(-100..=100, -128)].into_iter().collect();
+        assert_eq!([-100, 0, 100].map(|v| shifted.map(v)), [-128, -28, 72]);
+        shifted.insert(-50..=50, 0);
+        assert_eq!([-100, -51, -50, 50, 51, 100].map(|v| shifted.map(v)), [-128, -79, 0, 100, 23, 72]);
+    }
+}
@@ -0,0 +1,91 @@
+/// A lightweight global allocator that counts allocations.
+/// Used to report allocation statistics when benching, without the overhead of a full heap profiler.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Wraps the system allocator and counts every allocation made through it.
+/// The `solution!` macro installs this as the global allocator when the `count-allocs` feature is enabled.
+pub struct CountingAlloc;
+
+fn record(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(size as u64, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// A snapshot of the allocation counters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocCounts {
+    pub allocations: u64,
+    pub bytes: u64,
+}
+
+impl AllocCounts {
+    /// Reads the current value of the allocation counters.
+    /// Both values stay at zero if [`CountingAlloc`] is not installed.
+    pub fn now() -> Self {
+        Self {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            bytes: BYTES.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the allocations made between `earlier` and `self`.
+    #[must_use]
+    pub fn since(self, earlier: Self) -> Self {
+        Self {
+            allocations: self.allocations.saturating_sub(earlier.allocations),
+            bytes: self.bytes.saturating_sub(earlier.bytes),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::AllocCounts;
+
+    #[test]
+    fn computes_difference_between_snapshots() {
+        let earlier = AllocCounts {
+            allocations: 10,
+            bytes: 1024,
+        };
+        let later = AllocCounts {
+            allocations: 15,
+            bytes: 4096,
+        };
+        assert_eq!(
+            later.since(earlier),
+            AllocCounts {
+                allocations: 5,
+                bytes: 3072
+            }
+        );
+        assert_eq!(earlier.since(later), AllocCounts::default());
+    }
+}
@@ -0,0 +1,696 @@
+/// A small declarative command-line parser.
+/// Commands and their arguments are described once, and help texts, validation and shell completions are derived
+/// from that description.
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The kind of a command-line argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    /// A boolean switch, e.g. `--release`.
+    Flag,
+    /// A named argument that takes a value, e.g. `--submit <PART>`.
+    /// If the value is optional, the following argument is only taken as value if it does not start with `-`.
+    Option {
+        value_name: &'static str,
+        optional: bool,
+    },
+    /// A free argument, identified by its position.
+    Positional { required: bool },
+}
+
+/// Describes a single argument of a [`Command`].
+#[derive(Debug, Clone, Copy)]
+pub struct Arg {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: ArgKind,
+    pub conflicts_with: &'static [&'static str],
+}
+
+impl Arg {
+    pub const fn flag(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind: ArgKind::Flag,
+            conflicts_with: &[],
+        }
+    }
+
+    pub const fn option(name: &'static str, value_name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind: ArgKind::Option {
+                value_name,
+                optional: false,
+            },
+            conflicts_with: &[],
+        }
+    }
+
+    /// A named argument whose value may be omitted, e.g. `--example [N]`.
+    /// Use [`Matches::flag`] to check if it was passed at all.
+    pub const fn optional_value(
+        name: &'static str,
+        value_name: &'static str,
+        help: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            help,
+            kind: ArgKind::Option {
+                value_name,
+                optional: true,
+            },
+            conflicts_with: &[],
+        }
+    }
+
+    pub const fn positional(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind: ArgKind::Positional { required: false },
+            conflicts_with: &[],
+        }
+    }
+
+    pub const fn required(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind: ArgKind::Positional { required: true },
+            conflicts_with: &[],
+        }
+    }
+
+    /// Marks this argument as invalid in combination with the given arguments.
+    #[must_use]
+    pub const fn conflicts_with(mut self, names: &'static [&'static str]) -> Self {
+        self.conflicts_with = names;
+        self
+    }
+
+    fn usage(&self) -> String {
+        match self.kind {
+            ArgKind::Flag => self.name.to_string(),
+            ArgKind::Option {
+                value_name,
+                optional: false,
+            } => format!("{} <{value_name}>", self.name),
+            ArgKind::Option {
+                value_name,
+                optional: true,
+            } => format!("{} [{value_name}]", self.name),
+            ArgKind::Positional { required: true } => format!("<{}>", self.name),
+            ArgKind::Positional { required: false } => format!("[{}]", self.name),
+        }
+    }
+}
+
+/// Describes a subcommand and its arguments.
+#[derive(Debug, Clone, Copy)]
+pub struct Command {
+    pub name: &'static str,
+    pub about: &'static str,
+    pub args: &'static [Arg],
+}
+
+impl Command {
+    pub const fn new(name: &'static str, about: &'static str, args: &'static [Arg]) -> Self {
+        Self { name, about, args }
+    }
+
+    fn positionals(&self) -> impl Iterator<Item = &Arg> {
+        self.args
+            .iter()
+            .filter(|a| matches!(a.kind, ArgKind::Positional { .. }))
+    }
+
+    fn named(&self) -> impl Iterator<Item = &Arg> {
+        self.args
+            .iter()
+            .filter(|a| !matches!(a.kind, ArgKind::Positional { .. }))
+    }
+
+    /// Renders the help text for this command.
+    pub fn help(&self, program: &str) -> String {
+        let mut usage = format!("{program} {}", self.name);
+        for arg in self.positionals() {
+            usage.push(' ');
+            usage.push_str(&arg.usage());
+        }
+        if self.named().next().is_some() {
+            usage.push_str(" [OPTIONS]");
+        }
+
+        let mut lines = vec![
+            self.about.to_string(),
+            String::new(),
+            format!("Usage: {usage}"),
+        ];
+
+        if self.positionals().next().is_some() {
+            lines.push(String::new());
+            lines.push("Arguments:".into());
+            lines.extend(self.positionals().map(|a| format_entry(&a.usage(), a.help)));
+        }
+
+        lines.push(String::new());
+        lines.push("Options:".into());
+        lines.extend(self.named().map(|a| format_entry(&a.usage(), a.help)));
+        lines.push(format_entry("-h, --help", "Print help"));
+
+        lines.join("\n")
+    }
+}
+
+fn format_entry(usage: &str, help: &str) -> String {
+    format!("  {usage:<24} {help}")
+}
+
+/// Renders the help text listing all commands.
+pub fn help(program: &str, about: &str, commands: &[Command]) -> String {
+    let mut lines = vec![
+        about.to_string(),
+        String::new(),
+        format!("Usage: {program} <COMMAND> [OPTIONS]"),
+        String::new(),
+        "Commands:".into(),
+    ];
+
+    lines.extend(commands.iter().map(|c| format_entry(c.name, c.about)));
+    lines.push(format_entry("help", "Print help for a command"));
+    lines.push(String::new());
+    lines.push(format!(
+        "Run `{program} <COMMAND> --help` for more information on a command."
+    ));
+
+    lines.join("\n")
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An error or early exit returned by [`parse`].
+#[derive(Debug)]
+pub enum CliError {
+    /// Help was requested. Contains the rendered help text.
+    Help(String),
+    /// The arguments are invalid.
+    Usage(String),
+}
+
+impl std::error::Error for CliError {}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Help(s) | CliError::Usage(s) => f.write_str(s),
+        }
+    }
+}
+
+/// The arguments passed to a command, validated against its description.
+#[derive(Debug)]
+pub struct Matches {
+    pub command: &'static str,
+    flags: HashSet<&'static str>,
+    values: HashMap<&'static str, String>,
+    positionals: HashMap<&'static str, String>,
+}
+
+impl Matches {
+    /// Whether the given flag was passed.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    /// Parses the value of an option or positional argument, if present.
+    pub fn value<T>(&self, name: &str) -> Result<Option<T>, CliError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.values
+            .get(name)
+            .or_else(|| self.positionals.get(name))
+            .map(|v| {
+                v.parse()
+                    .map_err(|e| CliError::Usage(format!("invalid value `{v}` for `{name}`: {e}")))
+            })
+            .transpose()
+    }
+
+    /// Parses the value of a required argument.
+    pub fn required<T>(&self, name: &str) -> Result<T, CliError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.value(name)?
+            .ok_or_else(|| CliError::Usage(format!("missing required argument `{name}`")))
+    }
+}
+
+/// Parses command-line arguments (without the program name) against the given commands.
+pub fn parse(
+    program: &str,
+    about: &str,
+    commands: &[Command],
+    args: &[String],
+) -> Result<Matches, CliError> {
+    let Some((name, rest)) = args.split_first() else {
+        return Err(CliError::Usage(format!(
+            "no command specified.\n\n{}",
+            help(program, about, commands)
+        )));
+    };
+
+    if name == "help" || name == "--help" || name == "-h" {
+        return Err(CliError::Help(
+            match rest.first().and_then(|c| find(commands, c)) {
+                Some(command) => command.help(program),
+                None => help(program, about, commands),
+            },
+        ));
+    }
+
+    let command = find(commands, name).ok_or_else(|| {
+        CliError::Usage(format!(
+            "unknown command `{name}`.\n\n{}",
+            help(program, about, commands)
+        ))
+    })?;
+
+    if rest.iter().any(|a| a == "--help" || a == "-h") {
+        return Err(CliError::Help(command.help(program)));
+    }
+
+    let usage_error = |message: String| {
+        CliError::Usage(format!(
+            "{message}\n\nRun `{program} {} --help` for usage.",
+            command.name
+        ))
+    };
+
+    let mut matches = Matches {
+        command: command.name,
+        flags: HashSet::new(),
+        values: HashMap::new(),
+        positionals: HashMap::new(),
+    };
+
+    let mut positionals = command.positionals();
+    let mut iter = rest.iter().peekable();
+
+    while let Some(arg) = iter.next() {
+        if arg.starts_with("--") {
+            let (key, inline_value) = match arg.split_once('=') {
+                Some((key, value)) => (key, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+
+            let spec = command
+                .named()
+                .find(|a| a.name == key)
+                .ok_or_else(|| usage_error(format!("unexpected argument `{key}`.")))?;
+
+            match spec.kind {
+                ArgKind::Flag => {
+                    if inline_value.is_some() {
+                        return Err(usage_error(format!("`{key}` does not take a value.")));
+                    }
+                    matches.flags.insert(spec.name);
+                }
+                ArgKind::Option {
+                    value_name,
+                    optional: false,
+                } => {
+                    let value = inline_value
+                        .or_else(|| iter.next().cloned())
+                        .ok_or_else(|| usage_error(format!("`{key}` expects a <{value_name}>.")))?;
+                    matches.values.insert(spec.name, value);
+                }
+                ArgKind::Option { optional: true, .. } => {
+                    let value = inline_value
+                        .or_else(|| iter.next_if(|next| !next.starts_with('-')).cloned());
+                    matches.flags.insert(spec.name);
+                    if let Some(value) = value {
+                        matches.values.insert(spec.name, value);
+                    }
+                }
+                ArgKind::Positional { .. } => unreachable!(),
+            }
+        } else {
+            let spec = positionals
+                .next()
+                .ok_or_else(|| usage_error(format!("unexpected argument `{arg}`.")))?;
+            matches.positionals.insert(spec.name, arg.clone());
+        }
+    }
+
+    for spec in command.args {
+        let is_present = |name: &str| {
+            matches.flags.contains(name)
+                || matches.values.contains_key(name)
+                || matches.positionals.contains_key(name)
+        };
+
+        if let ArgKind::Positional { required: true } = spec.kind {
+            if !is_present(spec.name) {
+                return Err(usage_error(format!(
+                    "missing required argument <{}>.",
+                    spec.name
+                )));
+            }
+        }
+
+        if is_present(spec.name) {
+            if let Some(other) = spec.conflicts_with.iter().find(|name| is_present(name)) {
+                return Err(usage_error(format!(
+                    "`{}` cannot be used together with `{other}`.",
+                    spec.name
+                )));
+            }
+        }
+    }
+
+    Ok(matches)
+}
+
+fn find<'a>(commands: &'a [Command], name: &str) -> Option<&'a Command> {
+    commands.iter().find(|c| c.name == name)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Shells supported by [`completions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            _ => Err("expected one of `bash`, `zsh` or `fish`".into()),
+        }
+    }
+}
+
+/// Generates a completion script for `cargo <COMMAND>` aliases and `cargo <prefix> <COMMAND>`.
+/// Completions for other cargo commands are delegated to the shell's regular cargo completions.
+pub fn completions(shell: Shell, prefix: &str, commands: &[Command]) -> String {
+    match shell {
+        Shell::Bash => bash_completions(prefix, commands),
+        Shell::Zsh => zsh_completions(prefix, commands),
+        Shell::Fish => fish_completions(prefix, commands),
+    }
+}
+
+fn command_names(commands: &[Command]) -> String {
+    commands
+        .iter()
+        .map(|c| c.name)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bash_completions(prefix: &str, commands: &[Command]) -> String {
+    let names = command_names(commands);
+
+    let cases: String = commands
+        .iter()
+        .map(|c| {
+            let flags = c.named().map(|a| a.name).collect::<Vec<_>>().join(" ");
+            format!("        {}) opts=\"{flags} --help\" ;;\n", c.name)
+        })
+        .collect();
+
+    format!(
+        r#"# bash completions for `cargo {prefix} <COMMAND>` and its `cargo <COMMAND>` aliases.
+_cargo_{prefix}() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local first="${{COMP_WORDS[1]}}"
+    local cmd offset
+
+    if [[ "$first" == "{prefix}" ]]; then
+        cmd="${{COMP_WORDS[2]}}"
+        offset=2
+    elif [[ " {names} " == *" $first "* ]]; then
+        cmd="$first"
+        offset=1
+    else
+        declare -F _cargo >/dev/null && _cargo "$@"
+        return
+    fi
+
+    if [[ $COMP_CWORD -eq $offset ]]; then
+        COMPREPLY=($(compgen -W "{names}" -- "$cur"))
+        return
+    fi
+
+    local opts=""
+    case "$cmd" in
+{cases}    esac
+    COMPREPLY=($(compgen -W "$opts" -- "$cur"))
+}}
+
+complete -F _cargo_{prefix} cargo
+"#
+    )
+}
+
+fn zsh_escape(s: &str) -> String {
+    s.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+fn zsh_completions(prefix: &str, commands: &[Command]) -> String {
+    let names = command_names(commands);
+
+    let descriptions: String = commands
+        .iter()
+        .map(|c| format!("        '{}:{}'\n", c.name, zsh_escape(c.about)))
+        .collect();
+
+    let cases: String = commands
+        .iter()
+        .map(|c| {
+            let specs: Vec<String> = c
+                .args
+                .iter()
+                .map(|a| match a.kind {
+                    ArgKind::Flag => format!("'{}[{}]'", a.name, zsh_escape(a.help)),
+                    ArgKind::Option {
+                        value_name,
+                        optional,
+                    } => format!(
+                        "'{}[{}]:{}{}:'",
+                        a.name,
+                        zsh_escape(a.help),
+                        if optional { ":" } else { "" },
+                        value_name.to_lowercase()
+                    ),
+                    ArgKind::Positional { required } => format!(
+                        "'{}:{}:'",
+                        if required { "1" } else { ":1" },
+                        a.name.to_lowercase()
+                    ),
+                })
+                .collect();
+            format!(
+                "            {}) _arguments {} ;;\n",
+                c.name,
+                specs.join(" ")
+            )
+        })
+        .collect();
+
+    format!(
+        r#"#compdef cargo
+# zsh completions for `cargo {prefix} <COMMAND>` and its `cargo <COMMAND>` aliases.
+_cargo_{prefix}() {{
+    local offset
+
+    if [[ ${{words[2]}} == {prefix} ]]; then
+        offset=2
+    elif [[ " {names} " == *" ${{words[2]}} "* ]]; then
+        offset=1
+    else
+        (( $+functions[_cargo] )) && _cargo "$@"
+        return
+    fi
+
+    if (( CURRENT == offset + 1 )); then
+        local -a commands
+        commands=(
+{descriptions}        )
+        _describe 'command' commands
+        return
+    fi
+
+    words=(${{words[offset+1,-1]}})
+    (( CURRENT -= offset ))
+
+    case ${{words[1]}} in
+{cases}    esac
+}}
+
+compdef _cargo_{prefix} cargo
+"#
+    )
+}
+
+fn fish_completions(prefix: &str, commands: &[Command]) -> String {
+    let names = command_names(commands);
+    let mut lines = vec![format!(
+        "# fish completions for `cargo {prefix} <COMMAND>` and its `cargo <COMMAND>` aliases."
+    )];
+
+    for command in commands {
+        lines.push(format!(
+            "complete -c cargo -n '__fish_seen_subcommand_from {prefix}; and not __fish_seen_subcommand_from {names}' -f -a '{}' -d '{}'",
+            command.name,
+            command.about.replace('\'', "\\'")
+        ));
+
+        for arg in command.named() {
+            let long = arg.name.trim_start_matches("--");
+            let requires_value = if matches!(
+                arg.kind,
+                ArgKind::Option {
+                    optional: false,
+                    ..
+                }
+            ) {
+                " -r"
+            } else {
+                ""
+            };
+            lines.push(format!(
+                "complete -c cargo -n '__fish_seen_subcommand_from {}' -l {long}{requires_value} -d '{}'",
+                command.name,
+                arg.help.replace('\'', "\\'")
+            ));
+        }
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{completions, parse, Arg, CliError, Command, Shell};
+
+    const COMMANDS: &[Command] = &[
+        Command::new(
+            "solve",
+            "Run a solution.",
+            &[
+                Arg::required("DAY", "The day to run"),
+                Arg::flag("--release", "Optimized build"),
+                Arg::flag("--dhat", "Profile").conflicts_with(&["--release"]),
+                Arg::option("--submit", "PART", "Submit a part"),
+                Arg::optional_value("--example", "N", "Use an example"),
+            ],
+        ),
+        Command::new(
+            "all",
+            "Run all solutions.",
+            &[Arg::positional("DAYS", "Days")],
+        ),
+    ];
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    fn run(s: &str) -> Result<super::Matches, CliError> {
+        parse("cargo", "Test", COMMANDS, &args(s))
+    }
+
+    #[test]
+    fn parses_flags_options_and_positionals() {
+        let matches = run("solve 5 --release --submit 2").unwrap();
+        assert_eq!(matches.command, "solve");
+        assert_eq!(matches.required::<u8>("DAY").unwrap(), 5);
+        assert!(matches.flag("--release"));
+        assert!(!matches.flag("--dhat"));
+        assert_eq!(matches.value::<u8>("--submit").unwrap(), Some(2));
+    }
+
+    #[test]
+    fn parses_inline_values() {
+        let matches = run("solve 5 --submit=1").unwrap();
+        assert_eq!(matches.value::<u8>("--submit").unwrap(), Some(1));
+    }
+
+    #[test]
+    fn parses_optional_values() {
+        let matches = run("solve 5 --example --release").unwrap();
+        assert!(matches.flag("--example"));
+        assert_eq!(matches.value::<u8>("--example").unwrap(), None);
+        assert!(matches.flag("--release"));
+
+        let matches = run("solve 5 --example 2").unwrap();
+        assert!(matches.flag("--example"));
+        assert_eq!(matches.value::<u8>("--example").unwrap(), Some(2));
+
+        let matches = run("solve 5 --example=3").unwrap();
+        assert_eq!(matches.value::<u8>("--example").unwrap(), Some(3));
+
+        let matches = run("solve 5").unwrap();
+        assert!(!matches.flag("--example"));
+    }
+
+    #[test]
+    fn returns_help() {
+        assert!(matches!(run("--help"), Err(CliError::Help(_))));
+        assert!(
+            matches!(run("help solve"), Err(CliError::Help(s)) if s.contains("Usage: cargo solve <DAY> [OPTIONS]"))
+        );
+        assert!(matches!(run("all -h"), Err(CliError::Help(s)) if s.contains("[DAYS]")));
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(matches!(run(""), Err(CliError::Usage(_))));
+        assert!(matches!(run("foo"), Err(CliError::Usage(_))));
+        assert!(matches!(run("solve"), Err(CliError::Usage(_))));
+        assert!(matches!(run("solve 1 --unknown"), Err(CliError::Usage(_))));
+        assert!(matches!(run("solve 1 2"), Err(CliError::Usage(_))));
+        assert!(matches!(run("solve 1 --submit"), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn rejects_conflicting_arguments() {
+        assert!(
+            matches!(run("solve 1 --dhat --release"), Err(CliError::Usage(s)) if s.contains("`--dhat` cannot be used together with `--release`"))
+        );
+    }
+
+    #[test]
+    fn generates_completions() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let script = completions(shell, "aoc", COMMANDS);
+            assert!(script.contains("solve"));
+            assert!(script.contains("--dhat") || script.contains("-l dhat"));
+        }
+    }
+}
@@ -1,5 +1,19 @@
-use crate::template::{all_days, run_multi::run_multi};
+use crate::template::{
+    run_multi::{run_multi, RunOptions},
+    timings::Timings,
+    DayFilters, DaySet, Limits,
+};
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+pub fn handle(days: Option<DaySet>, filters: DayFilters, is_release: bool, limits: Limits) {
+    let mut days_to_run = days.unwrap_or_else(DaySet::all);
+    days_to_run.apply_filters(&filters, &Timings::read_from_file());
+
+    run_multi(
+        &days_to_run,
+        &RunOptions {
+            is_release,
+            limits,
+            ..Default::default()
+        },
+    );
 }
@@ -1,8 +1,16 @@
 use std::process::{Command, Stdio};
 
-use crate::template::Day;
+use crate::template::config::Config;
+use crate::template::{Day, InputSource};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+pub fn handle(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    input: &InputSource,
+    time: bool,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -12,11 +20,20 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
             "--features".to_string(),
             "dhat-heap".to_string(),
         ]);
-    } else if release {
+    } else if release || time {
         cmd_args.push("--release".to_string());
     }
 
+    if time && !dhat {
+        cmd_args.extend(["--features".to_string(), "count-allocs".to_string()]);
+    }
+
     cmd_args.push("--".to_string());
+    cmd_args.extend(input.to_args());
+
+    if time {
+        cmd_args.push("--time".to_string());
+    }
 
     if let Some(submit_part) = submit_part {
         cmd_args.push("--submit".to_string());
@@ -25,6 +42,7 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
 
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
+        .envs(Config::get().env_vars())
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
         .spawn()
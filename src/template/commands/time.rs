@@ -1,27 +1,36 @@
-use std::collections::HashSet;
-
-use crate::template::run_multi::run_multi;
+use crate::template::run_multi::{run_multi, RunOptions};
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{readme_benchmarks, DayFilters, DaySet, Limits};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    days: Option<DaySet>,
+    run_all: bool,
+    mut filters: DayFilters,
+    store: bool,
+    memory: bool,
+    perf: bool,
+    limits: Limits,
+) {
     let stored_timings = Timings::read_from_file();
 
-    let days_to_run = day.map_or_else(
-        || {
-            if run_all {
-                all_days().collect()
-            } else {
-                // when the `--all` flag is not set, filter out days that are fully benched.
-                all_days()
-                    .filter(|day| !stored_timings.is_day_complete(*day))
-                    .collect()
-            }
-        },
-        |day| HashSet::from([day]),
-    );
+    // when neither days nor the `--all` flag are set, filter out days that are fully benched.
+    if days.is_none() && !run_all {
+        filters.unsolved = true;
+    }
+
+    let mut days_to_run = days.unwrap_or_else(DaySet::all);
+    days_to_run.apply_filters(&filters, &stored_timings);
+
+    let options = RunOptions {
+        is_release: true,
+        is_timed: true,
+        is_memory: memory,
+        is_perf: perf,
+        limits,
+    };
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let timings = run_multi(&days_to_run, &options).unwrap();
 
     if store {
         let merged_timings = stored_timings.merge(&timings);
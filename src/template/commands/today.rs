@@ -0,0 +1,111 @@
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+
+use chrono::{DateTime, Utc};
+
+use crate::template::commands::{download, read, scaffold};
+use crate::template::config::{Config, OutputFormat};
+use crate::template::fetch::{AocCli, Fetch, FetchError, HttpFetcher};
+use crate::template::unlock::{self, Backoff, Clock, SystemClock};
+use crate::template::{aoc_cli, Day};
+
+pub fn handle(wait: bool) {
+    if wait {
+        return handle_wait(&SystemClock);
+    }
+
+    match Day::today() {
+        Some(day) => {
+            scaffold::handle(day, false);
+            download::handle(day);
+            read::handle(day);
+        }
+        None => {
+            eprintln!(
+                "`today` command can only be run between the 1st and \
+                the 25th of december. Please use `scaffold` with a specific day \
+                or `today --wait` to wait for the next puzzle."
+            );
+            process::exit(1)
+        }
+    }
+}
+
+/// Scaffolds the next day in advance, counts down to its unlock and downloads it as soon as it is available.
+fn handle_wait(clock: &impl Clock) {
+    let config = Config::get();
+
+    let Some((year, day, unlocks_at)) = unlock::next_unlock(clock.now()) else {
+        eprintln!("All puzzles of this year's event have unlocked. Please use `scaffold` with a specific day.");
+        process::exit(1);
+    };
+
+    if let Some(configured) = config.year.filter(|configured| *configured != year) {
+        eprintln!(
+            "The next puzzle is part of the {year} event, but the year is set to {configured}. \
+            Please update `year` in aoc.toml or set `AOC_YEAR={year}`."
+        );
+        process::exit(1);
+    }
+
+    if config.server.url.is_none() && aoc_cli::check().is_err() {
+        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+        process::exit(1);
+    }
+
+    if Path::new(&format!("src/bin/{day}.rs")).exists() {
+        println!("Module file \"src/bin/{day}.rs\" already exists, skipping scaffold.");
+    } else {
+        scaffold::handle(day, false);
+    }
+
+    wait(clock, day, unlocks_at, config.output.format);
+
+    let result = match &config.server.url {
+        Some(url) => fetch(clock, &mut HttpFetcher::new(url, year, config), day),
+        None => fetch(clock, &mut AocCli, day),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Failed to download day {day}: {e}");
+        process::exit(1);
+    }
+
+    if config.server.url.is_none() {
+        read::handle(day);
+    }
+}
+
+fn wait(clock: &impl Clock, day: Day, unlocks_at: DateTime<Utc>, format: OutputFormat) {
+    let remaining = unlocks_at - clock.now();
+    let remaining = remaining.to_std().unwrap_or_default();
+    println!(
+        "⏳ Day {day} unlocks at {unlocks_at} (in {}).",
+        unlock::format_countdown(remaining)
+    );
+
+    if format == OutputFormat::Plain {
+        unlock::wait_until(clock, unlocks_at, |_| {});
+        println!("🔓 Day {day} is unlocked!");
+        return;
+    }
+
+    let mut stdout = io::stdout();
+    unlock::wait_until(clock, unlocks_at, |remaining| {
+        // errors while drawing the countdown don't matter, the download does.
+        let _ = write!(
+            stdout,
+            "\r\x1b[2K⏳ {}",
+            unlock::format_countdown(remaining)
+        );
+        let _ = stdout.flush();
+    });
+    println!("\r\x1b[2K🔓 Day {day} is unlocked!");
+}
+
+fn fetch(clock: &impl Clock, fetcher: &mut impl Fetch, day: Day) -> Result<(), FetchError> {
+    unlock::fetch_with_retry(clock, fetcher, day, Backoff::default(), |e, delay| {
+        eprintln!("Download failed: {e} Retrying in {}s.", delay.as_secs());
+    })
+}
@@ -0,0 +1,692 @@
+/// Project configuration, read from `aoc.toml` in the project root.
+///
+/// Values are resolved in the following order, later sources taking precedence:
+///  1. built-in defaults.
+///  2. the config file (`aoc.toml`, or the path in `AOC_CONFIG`).
+///  3. environment variables (`AOC_YEAR`, `AOC_SESSION_FILE`, `AOC_SERVER_URL`, `AOC_OUTPUT`, `AOC_BENCH_TARGET_MS`).
+///  4. command-line flags. These are forwarded to solution binaries as environment variables.
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::{env, fs, io, process};
+
+pub static CONFIG_FILE_PATH: &str = "aoc.toml";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Config {
+    /// The puzzle year. If not set, aoc-cli picks the year of the current or last event.
+    pub year: Option<u16>,
+    pub paths: Paths,
+    pub session: Session,
+    pub server: Server,
+    pub bench: Bench,
+    pub template: Template,
+    pub readme: Readme,
+    pub output: Output,
+}
+
+/// Locations of puzzle data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paths {
+    pub inputs: PathBuf,
+    pub examples: PathBuf,
+    pub puzzles: PathBuf,
+    pub timings: PathBuf,
+}
+
+/// Where aoc-cli reads the session cookie from.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Session {
+    /// A file containing the session cookie. If not set, aoc-cli uses its own default.
+    pub file: Option<PathBuf>,
+}
+
+/// Where `cargo today --wait` downloads puzzles from.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Server {
+    /// An `http://` server to download from directly, e.g. a mirror or a mock server for testing.
+    /// If not set, puzzles are downloaded from adventofcode.com with aoc-cli.
+    pub url: Option<String>,
+}
+
+/// Budgets for `cargo time`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bench {
+    /// The approximate time a part is benched for.
+    pub target_ms: u64,
+    pub min_samples: u64,
+    pub max_samples: u64,
+}
+
+/// The template used by `cargo scaffold`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Template {
+    /// A custom template file. If not set, the built-in template is used.
+    pub path: Option<PathBuf>,
+}
+
+/// Where `cargo time --store` writes the benchmark table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Readme {
+    pub path: PathBuf,
+    /// The comment that delimits the benchmark table.
+    pub marker: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Output {
+    pub format: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Styled output with progress indicators.
+    #[default]
+    Pretty,
+    /// Output without ANSI escape codes or progress indicators, e.g. for logs and CI.
+    Plain,
+}
+
+impl Default for Paths {
+    fn default() -> Self {
+        Self {
+            inputs: "data/inputs".into(),
+            examples: "data/examples".into(),
+            puzzles: "data/puzzles".into(),
+            timings: "data/timings.json".into(),
+        }
+    }
+}
+
+impl Default for Bench {
+    fn default() -> Self {
+        Self {
+            target_ms: 1000,
+            min_samples: 10,
+            max_samples: 10000,
+        }
+    }
+}
+
+impl Default for Readme {
+    fn default() -> Self {
+        Self {
+            path: "README.md".into(),
+            marker: "<!--- benchmarking table --->".into(),
+        }
+    }
+}
+
+impl Config {
+    /// Returns the configuration of the current process, loading it on first access.
+    /// Exits the process if the config file is invalid.
+    pub fn get() -> &'static Config {
+        CONFIG.get_or_init(|| {
+            Self::load().unwrap_or_else(|e| {
+                eprintln!("{e}");
+                process::exit(1);
+            })
+        })
+    }
+
+    /// Sets the configuration of the current process, e.g. after applying command-line flags.
+    ///
+    /// # Panics
+    /// If the configuration was already accessed or set.
+    pub fn set(config: Config) {
+        CONFIG
+            .set(config)
+            .expect("config should only be set once, before it is first accessed");
+    }
+
+    /// Loads the config file and applies environment variables.
+    pub fn load() -> Result<Self, ConfigError> {
+        let path = env::var("AOC_CONFIG").unwrap_or_else(|_| CONFIG_FILE_PATH.into());
+
+        let mut config = match fs::read_to_string(&path) {
+            Ok(s) => s.parse().map_err(|e: ConfigError| e.in_file(&path))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(ConfigError::IO(e)),
+        };
+
+        config.apply_env(|key| env::var(key).ok())?;
+        Ok(config)
+    }
+
+    fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
+        if let Some(year) = var("AOC_YEAR") {
+            self.year = Some(parse_env("AOC_YEAR", &year)?);
+        }
+        if let Some(file) = var("AOC_SESSION_FILE") {
+            self.session.file = Some(file.into());
+        }
+        if let Some(url) = var("AOC_SERVER_URL") {
+            if !url.starts_with("http://") {
+                return Err(ConfigError::Env(format!(
+                    "invalid value `{url}` for `AOC_SERVER_URL`, expected an `http://` url."
+                )));
+            }
+            self.server.url = Some(url);
+        }
+        if let Some(format) = var("AOC_OUTPUT") {
+            self.output.format = parse_env("AOC_OUTPUT", &format)?;
+        }
+        if let Some(target_ms) = var("AOC_BENCH_TARGET_MS") {
+            self.bench.target_ms = parse_env("AOC_BENCH_TARGET_MS", &target_ms)?;
+        }
+        Ok(())
+    }
+
+    /// Environment variables that pass the values overridable on the command line on to child processes.
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let mut vars = vec![
+            ("AOC_OUTPUT", self.output.format.to_string()),
+            ("AOC_BENCH_TARGET_MS", self.bench.target_ms.to_string()),
+        ];
+        if let Some(year) = self.year {
+            vars.push(("AOC_YEAR", year.to_string()));
+        }
+        if let Some(file) = &self.session.file {
+            vars.push(("AOC_SESSION_FILE", file.to_string_lossy().into()));
+        }
+        vars
+    }
+
+    /// Resolves a data folder name, e.g. `inputs`, to its configured directory.
+    /// Unknown folders are resolved relative to the parent directory of the inputs directory.
+    pub fn data_dir(&self, folder: &str) -> PathBuf {
+        match folder {
+            "inputs" => self.paths.inputs.clone(),
+            "examples" => self.paths.examples.clone(),
+            "puzzles" => self.paths.puzzles.clone(),
+            _ => self
+                .paths
+                .inputs
+                .parent()
+                .unwrap_or(Path::new(""))
+                .join(folder),
+        }
+    }
+
+    /// The session file, with a leading `~` expanded to the home directory.
+    pub fn session_file(&self) -> Option<PathBuf> {
+        let file = self.session.file.as_ref()?;
+        match (file.strip_prefix("~"), env::var_os("HOME")) {
+            (Ok(rest), Some(home)) => Some(Path::new(&home).join(rest)),
+            _ => Some(file.clone()),
+        }
+    }
+}
+
+fn parse_env<T: FromStr>(key: &str, value: &str) -> Result<T, ConfigError> {
+    value
+        .parse()
+        .map_err(|_| ConfigError::Env(format!("invalid value `{value}` for `{key}`.")))
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pretty" => Ok(OutputFormat::Pretty),
+            "plain" => Ok(OutputFormat::Plain),
+            _ => Err("expected `pretty` or `plain`".into()),
+        }
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputFormat::Pretty => write!(f, "pretty"),
+            OutputFormat::Plain => write!(f, "plain"),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl FromStr for Config {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Config::default();
+
+        for (key, (line, value)) in parse_toml(s)? {
+            let invalid = |expected: &str| ConfigError::Parse {
+                file: None,
+                line,
+                message: format!("`{key}` must be {expected}."),
+            };
+
+            match key.as_str() {
+                "year" => {
+                    config.year = Some(value.as_integer().ok_or_else(|| invalid("a year"))?);
+                }
+                "paths.inputs" => {
+                    config.paths.inputs = value.as_path().ok_or_else(|| invalid("a path"))?
+                }
+                "paths.examples" => {
+                    config.paths.examples = value.as_path().ok_or_else(|| invalid("a path"))?;
+                }
+                "paths.puzzles" => {
+                    config.paths.puzzles = value.as_path().ok_or_else(|| invalid("a path"))?
+                }
+                "paths.timings" => {
+                    config.paths.timings = value.as_path().ok_or_else(|| invalid("a path"))?
+                }
+                "session.file" => {
+                    config.session.file = Some(value.as_path().ok_or_else(|| invalid("a path"))?);
+                }
+                "server.url" => {
+                    config.server.url = Some(
+                        value
+                            .as_str()
+                            .filter(|s| s.starts_with("http://"))
+                            .ok_or_else(|| invalid("an `http://` url"))?
+                            .into(),
+                    );
+                }
+                "bench.target_ms" => {
+                    config.bench.target_ms = value
+                        .as_integer()
+                        .filter(|ms| *ms > 0)
+                        .ok_or_else(|| invalid("a positive integer"))?;
+                }
+                "bench.min_samples" => {
+                    config.bench.min_samples = value
+                        .as_integer()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| invalid("a positive integer"))?;
+                }
+                "bench.max_samples" => {
+                    config.bench.max_samples = value
+                        .as_integer()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| invalid("a positive integer"))?;
+                }
+                "template.path" => {
+                    config.template.path = Some(value.as_path().ok_or_else(|| invalid("a path"))?);
+                }
+                "readme.path" => {
+                    config.readme.path = value.as_path().ok_or_else(|| invalid("a path"))?
+                }
+                "readme.marker" => {
+                    config.readme.marker = value
+                        .as_str()
+                        .filter(|s| !s.is_empty())
+                        .ok_or_else(|| invalid("a non-empty string"))?
+                        .into();
+                }
+                "output.format" => {
+                    config.output.format = value
+                        .as_str()
+                        .and_then(|s| s.parse().ok())
+                        .ok_or_else(|| invalid("`pretty` or `plain`"))?;
+                }
+                _ => {
+                    return Err(ConfigError::Parse {
+                        file: None,
+                        line,
+                        message: format!("unknown key `{key}`."),
+                    })
+                }
+            }
+        }
+
+        if config.bench.min_samples > config.bench.max_samples {
+            return Err(ConfigError::Parse {
+                file: None,
+                line: 0,
+                message: "`bench.min_samples` must not exceed `bench.max_samples`.".into(),
+            });
+        }
+
+        Ok(config)
+    }
+}
+
+/// A value of the TOML subset supported by the config file.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+}
+
+impl Value {
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_path(&self) -> Option<PathBuf> {
+        self.as_str().filter(|s| !s.is_empty()).map(PathBuf::from)
+    }
+
+    fn as_integer<T: TryFrom<i64>>(&self) -> Option<T> {
+        match self {
+            Value::Integer(i) => T::try_from(*i).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a subset of TOML: `[table]` headers and `key = value` pairs with string, integer, float and boolean values.
+/// Returns the values keyed by their dotted path, along with the line they were defined on.
+fn parse_toml(s: &str) -> Result<BTreeMap<String, (usize, Value)>, ConfigError> {
+    let mut values = BTreeMap::new();
+    let mut table = String::new();
+
+    for (i, raw) in s.lines().enumerate() {
+        let line = i + 1;
+        let error = |message: String| ConfigError::Parse {
+            file: None,
+            line,
+            message,
+        };
+
+        let content = strip_comment(raw).trim();
+
+        if content.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = content.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .map(str::trim)
+                .filter(|name| is_bare_key(name))
+                .ok_or_else(|| error(format!("invalid table header `{content}`.")))?;
+            table = name.into();
+            continue;
+        }
+
+        let (key, value) = content
+            .split_once('=')
+            .ok_or_else(|| error(format!("expected `key = value`, found `{content}`.")))?;
+
+        let key = key.trim();
+        if !is_bare_key(key) {
+            return Err(error(format!("invalid key `{key}`.")));
+        }
+
+        let value = parse_value(value.trim()).map_err(error)?;
+        let path = if table.is_empty() {
+            key.to_string()
+        } else {
+            format!("{table}.{key}")
+        };
+
+        if values.insert(path.clone(), (line, value)).is_some() {
+            return Err(error(format!("duplicate key `{path}`.")));
+        }
+    }
+
+    Ok(values)
+}
+
+fn is_bare_key(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Removes a trailing `# comment`, ignoring `#` inside of strings.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return &line[..i],
+            _ => {}
+        }
+        escaped = false;
+    }
+
+    line
+}
+
+fn parse_value(s: &str) -> Result<Value, String> {
+    if let Some(rest) = s.strip_prefix('"') {
+        let inner = rest
+            .strip_suffix('"')
+            .ok_or_else(|| format!("unterminated string `{s}`."))?;
+        return unescape(inner).map(Value::String);
+    }
+
+    if let Some(rest) = s.strip_prefix('\'') {
+        return rest
+            .strip_suffix('\'')
+            .map(|inner| Value::String(inner.into()))
+            .ok_or_else(|| format!("unterminated string `{s}`."));
+    }
+
+    match s {
+        "true" => return Ok(Value::Boolean(true)),
+        "false" => return Ok(Value::Boolean(false)),
+        _ => {}
+    }
+
+    let number = s.replace('_', "");
+    if let Ok(i) = number.parse() {
+        return Ok(Value::Integer(i));
+    }
+    if let Ok(f) = number.parse() {
+        return Ok(Value::Float(f));
+    }
+
+    Err(format!("unsupported value `{s}`."))
+}
+
+fn unescape(s: &str) -> Result<String, String> {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('\\') => result.push('\\'),
+            Some('"') => result.push('"'),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(c) => return Err(format!("unsupported escape sequence `\\{c}`.")),
+            None => return Err("unterminated escape sequence.".into()),
+        }
+    }
+
+    Ok(result)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum ConfigError {
+    IO(io::Error),
+    Parse {
+        file: Option<String>,
+        line: usize,
+        message: String,
+    },
+    Env(String),
+}
+
+impl ConfigError {
+    fn in_file(self, path: &str) -> Self {
+        match self {
+            ConfigError::Parse { line, message, .. } => ConfigError::Parse {
+                file: Some(path.into()),
+                line,
+                message,
+            },
+            e => e,
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::IO(e) => write!(f, "could not read config file: {e}"),
+            ConfigError::Parse {
+                file,
+                line,
+                message,
+            } => {
+                let file = file.as_deref().unwrap_or(CONFIG_FILE_PATH);
+                if *line == 0 {
+                    write!(f, "invalid config in {file}: {message}")
+                } else {
+                    write!(f, "invalid config in {file}:{line}: {message}")
+                }
+            }
+            ConfigError::Env(message) => write!(f, "invalid environment variable: {message}"),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_toml, Config, OutputFormat, Value};
+    use std::path::PathBuf;
+
+    #[test]
+    fn parses_empty_config() {
+        assert_eq!("".parse::<Config>().unwrap(), Config::default());
+    }
+
+    #[test]
+    fn parses_full_config() {
+        let config: Config = r#"
+            # comment
+            year = 2023
+
+            [paths]
+            inputs = "inputs" # trailing comment
+            timings = 'out/timings.json'
+
+            [session]
+            file = "~/.aoc#session"
+
+            [server]
+            url = "http://localhost:8080"
+
+            [bench]
+            target_ms = 2_000
+            max_samples = 500
+
+            [template]
+            path = "templates/day.rs"
+
+            [readme]
+            marker = "<!-- bench -->"
+
+            [output]
+            format = "plain"
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(config.year, Some(2023));
+        assert_eq!(config.paths.inputs, PathBuf::from("inputs"));
+        assert_eq!(config.paths.examples, PathBuf::from("data/examples"));
+        assert_eq!(config.paths.timings, PathBuf::from("out/timings.json"));
+        assert_eq!(config.session.file, Some(PathBuf::from("~/.aoc#session")));
+        assert_eq!(config.server.url.as_deref(), Some("http://localhost:8080"));
+        assert_eq!(config.bench.target_ms, 2000);
+        assert_eq!(config.bench.min_samples, 10);
+        assert_eq!(config.bench.max_samples, 500);
+        assert_eq!(
+            config.template.path,
+            Some(PathBuf::from("templates/day.rs"))
+        );
+        assert_eq!(config.readme.marker, "<!-- bench -->");
+        assert_eq!(config.output.format, OutputFormat::Plain);
+    }
+
+    #[test]
+    fn parses_values() {
+        let values = parse_toml("a = \"x\\\"y\"\nb = -3\nc = 1.5\nd = true").unwrap();
+        assert_eq!(values["a"].1, Value::String("x\"y".into()));
+        assert_eq!(values["b"].1, Value::Integer(-3));
+        assert_eq!(values["c"].1, Value::Float(1.5));
+        assert_eq!(values["d"].1, Value::Boolean(true));
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        let error = "[paths]\ninput = \"x\"".parse::<Config>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid config in aoc.toml:2: unknown key `paths.input`."
+        );
+
+        assert!("year = \"2023\"".parse::<Config>().is_err());
+        assert!("year = 2023\nyear = 2024".parse::<Config>().is_err());
+        assert!("[bench]\nmin_samples = 0".parse::<Config>().is_err());
+        assert!("[bench]\nmin_samples = 20\nmax_samples = 10"
+            .parse::<Config>()
+            .is_err());
+        assert!("[output]\nformat = \"fancy\"".parse::<Config>().is_err());
+        assert!("[server]\nurl = \"https://adventofcode.com\""
+            .parse::<Config>()
+            .is_err());
+        assert!("[paths\ninputs = \"x\"".parse::<Config>().is_err());
+        assert!("name = \"unterminated".parse::<Config>().is_err());
+    }
+
+    #[test]
+    fn applies_env_overrides() {
+        let mut config = Config::default();
+        config
+            .apply_env(|key| match key {
+                "AOC_YEAR" => Some("2022".into()),
+                "AOC_OUTPUT" => Some("plain".into()),
+                "AOC_SERVER_URL" => Some("http://127.0.0.1:3000".into()),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(config.year, Some(2022));
+        assert_eq!(config.output.format, OutputFormat::Plain);
+        assert_eq!(config.server.url.as_deref(), Some("http://127.0.0.1:3000"));
+        assert!(config
+            .apply_env(|key| (key == "AOC_BENCH_TARGET_MS").then(|| "soon".into()))
+            .is_err());
+        assert!(config
+            .apply_env(|key| (key == "AOC_SERVER_URL").then(|| "https://adventofcode.com".into()))
+            .is_err());
+        assert_eq!(config.server.url.as_deref(), Some("http://127.0.0.1:3000"));
+    }
+
+    #[test]
+    fn resolves_data_dirs() {
+        let config = Config::default();
+        assert_eq!(config.data_dir("inputs"), PathBuf::from("data/inputs"));
+        assert_eq!(config.data_dir("custom"), PathBuf::from("data/custom"));
+    }
+}
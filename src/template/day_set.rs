@@ -0,0 +1,225 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::template::config::Config;
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::timings::Timings;
+use crate::template::{all_days, Day};
+
+/// A set of days of advent, selected by an expression.
+///
+/// # Syntax
+/// An expression is a comma-separated list of terms:
+///  - a single day: `5`
+///  - a range of days: `1-10`
+///  - an exclusion of a day or range: `!5`, `!20-25`
+///
+/// Exclusions are applied after all other terms. An expression that only contains exclusions starts from all days.
+///
+/// ```
+/// # use advent_of_code::template::DaySet;
+/// let days: DaySet = "1-5,!3".parse().unwrap();
+/// assert_eq!(days.iter().map(|d| d.into_inner()).collect::<Vec<_>>(), vec![1, 2, 4, 5]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DaySet(BTreeSet<Day>);
+
+impl DaySet {
+    /// Creates a [`DaySet`] containing every day of advent.
+    pub fn all() -> Self {
+        Self(all_days().collect())
+    }
+
+    pub fn contains(&self, day: Day) -> bool {
+        self.0.contains(&day)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Iterates the days in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = Day> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Removes every day that does not match the given filters.
+    pub(crate) fn apply_filters(&mut self, filters: &DayFilters, timings: &Timings) {
+        if filters.unsolved {
+            self.0.retain(|day| !timings.is_day_complete(*day));
+        }
+
+        if let Some(threshold) = filters.slow_millis {
+            self.0.retain(|day| {
+                timings
+                    .data
+                    .iter()
+                    .any(|t| t.day == *day && t.total_nanos / 1_000_000_f64 > threshold)
+            });
+        }
+
+        if filters.changed {
+            let config = Config::get();
+            let last_run = fs::metadata(&config.paths.timings)
+                .and_then(|m| m.modified())
+                .ok();
+
+            if let Some(last_run) = last_run {
+                self.0.retain(|day| {
+                    [
+                        PathBuf::from(get_path_for_bin(*day)),
+                        config.paths.inputs.join(format!("{day}.txt")),
+                    ]
+                    .iter()
+                    .any(|path| {
+                        fs::metadata(path)
+                            .and_then(|m| m.modified())
+                            .is_ok_and(|modified| modified > last_run)
+                    })
+                });
+            }
+        }
+    }
+}
+
+impl FromIterator<Day> for DaySet {
+    fn from_iter<T: IntoIterator<Item = Day>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl From<Day> for DaySet {
+    fn from(day: Day) -> Self {
+        Self(BTreeSet::from([day]))
+    }
+}
+
+/// Filters that narrow down a [`DaySet`] based on stored timings and file modification times.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DayFilters {
+    /// Keep days that do not have stored results for both parts.
+    pub unsolved: bool,
+    /// Keep days whose stored total run time exceeds this many milliseconds.
+    pub slow_millis: Option<f64>,
+    /// Keep days whose solution or input changed since timings were last stored.
+    pub changed: bool,
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl FromStr for DaySet {
+    type Err = DaySetFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut included: Option<BTreeSet<Day>> = None;
+        let mut excluded: BTreeSet<Day> = BTreeSet::new();
+
+        for term in s.split(',').map(str::trim) {
+            if term.is_empty() {
+                return Err(DaySetFromStrError(format!("empty term in `{s}`")));
+            }
+
+            match term.strip_prefix('!') {
+                Some(term) => excluded.extend(parse_term(term)?),
+                None => included
+                    .get_or_insert_with(BTreeSet::new)
+                    .extend(parse_term(term)?),
+            }
+        }
+
+        let days = included
+            .unwrap_or_else(|| all_days().collect())
+            .difference(&excluded)
+            .copied()
+            .collect();
+
+        Ok(Self(days))
+    }
+}
+
+fn parse_day(s: &str) -> Result<Day, DaySetFromStrError> {
+    s.trim()
+        .parse()
+        .map_err(|_| DaySetFromStrError(format!("`{s}` is not a day number between 1 and 25")))
+}
+
+fn parse_term(term: &str) -> Result<Vec<Day>, DaySetFromStrError> {
+    match term.split_once('-') {
+        Some((start, end)) => {
+            let (start, end) = (parse_day(start)?, parse_day(end)?);
+            if start > end {
+                return Err(DaySetFromStrError(format!(
+                    "range `{term}` has its start after its end"
+                )));
+            }
+            Ok(all_days()
+                .filter(|day| *day >= start && *day <= end)
+                .collect())
+        }
+        None => Ok(vec![parse_day(term)?]),
+    }
+}
+
+/// An error which can be returned when parsing a [`DaySet`].
+#[derive(Debug)]
+pub struct DaySetFromStrError(String);
+
+impl Error for DaySetFromStrError {}
+
+impl Display for DaySetFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid day selection: {}", self.0)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::DaySet;
+
+    fn to_vec(days: &DaySet) -> Vec<u8> {
+        days.iter().map(|d| d.into_inner()).collect()
+    }
+
+    #[test]
+    fn parses_single_day() {
+        let days: DaySet = "7".parse().unwrap();
+        assert_eq!(to_vec(&days), vec![7]);
+    }
+
+    #[test]
+    fn parses_lists_and_ranges() {
+        let days: DaySet = "1,3-5, 10".parse().unwrap();
+        assert_eq!(to_vec(&days), vec![1, 3, 4, 5, 10]);
+    }
+
+    #[test]
+    fn parses_exclusions() {
+        let days: DaySet = "1-6,!2,!4-5".parse().unwrap();
+        assert_eq!(to_vec(&days), vec![1, 3, 6]);
+    }
+
+    #[test]
+    fn exclusions_only_start_from_all_days() {
+        let days: DaySet = "!1-20,!22".parse().unwrap();
+        assert_eq!(to_vec(&days), vec![21, 23, 24, 25]);
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!("0".parse::<DaySet>().is_err());
+        assert!("26".parse::<DaySet>().is_err());
+        assert!("5-3".parse::<DaySet>().is_err());
+        assert!("1,,2".parse::<DaySet>().is_err());
+        assert!("a-b".parse::<DaySet>().is_err());
+    }
+}
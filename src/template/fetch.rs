@@ -0,0 +1,380 @@
+/// Downloads of puzzle inputs and descriptions, either with aoc-cli or from a plain `http://` server.
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::time::Duration;
+use std::{env, fs};
+
+use crate::template::aoc_cli::{self, AocCommandError};
+use crate::template::config::Config;
+use crate::template::Day;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum FetchError {
+    Aoc(AocCommandError),
+    /// The server answered with a status other than `200 OK`, e.g. `404` before the puzzle unlocks.
+    Status(u16),
+    InvalidUrl(String),
+    InvalidResponse,
+    /// Connecting to or talking to the server failed.
+    Network(io::Error),
+    /// Writing the downloaded files failed.
+    IO(io::Error),
+}
+
+impl FetchError {
+    /// Whether trying again later might succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            FetchError::Aoc(e) => matches!(e, AocCommandError::BadExitStatus(_)),
+            FetchError::Status(status) => *status == 404 || *status >= 500,
+            FetchError::InvalidUrl(_) | FetchError::IO(_) => false,
+            FetchError::InvalidResponse | FetchError::Network(_) => true,
+        }
+    }
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::Aoc(e) => write!(f, "{e}"),
+            FetchError::Status(404) => write!(f, "the puzzle is not available yet (404)."),
+            FetchError::Status(status) => write!(f, "the server responded with status {status}."),
+            FetchError::InvalidUrl(url) => write!(f, "`{url}` is not an `http://` url."),
+            FetchError::InvalidResponse => write!(f, "the server sent an invalid response."),
+            FetchError::Network(e) => write!(f, "could not reach the server ({e})."),
+            FetchError::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl From<io::Error> for FetchError {
+    fn from(e: io::Error) -> Self {
+        FetchError::IO(e)
+    }
+}
+
+/// Downloads the input and puzzle description of a day.
+pub trait Fetch {
+    fn fetch(&mut self, day: Day) -> Result<(), FetchError>;
+}
+
+/// Downloads with aoc-cli, like `cargo download`.
+pub struct AocCli;
+
+impl Fetch for AocCli {
+    fn fetch(&mut self, day: Day) -> Result<(), FetchError> {
+        aoc_cli::download(day).map(|_| ()).map_err(FetchError::Aoc)
+    }
+}
+
+/// Downloads from an `http://` server that serves the same paths as adventofcode.com.
+///
+/// The input is written as-is. The puzzle description is written as the HTML of its `<article>` elements to
+/// `<puzzles>/<day>.html`, so it does not replace the Markdown that aoc-cli writes to `<puzzles>/<day>.md`.
+#[derive(Debug, Clone)]
+pub struct HttpFetcher {
+    pub url: String,
+    pub year: u16,
+    pub session: Option<String>,
+    pub inputs: PathBuf,
+    pub puzzles: PathBuf,
+}
+
+impl HttpFetcher {
+    /// Reads the session cookie from `ADVENT_OF_CODE_SESSION` or the configured session file,
+    /// like aoc-cli does. A missing session is not an error, some servers don't need one.
+    pub fn new(url: &str, year: u16, config: &Config) -> Self {
+        let session = env::var("ADVENT_OF_CODE_SESSION")
+            .ok()
+            .or_else(|| {
+                let file = config.session_file().or_else(|| {
+                    env::var_os("HOME")
+                        .map(|home| PathBuf::from(home).join(".adventofcode.session"))
+                })?;
+                fs::read_to_string(file).ok()
+            })
+            .map(|session| session.trim().to_string())
+            .filter(|session| !session.is_empty());
+
+        Self {
+            url: url.trim_end_matches('/').into(),
+            year,
+            session,
+            inputs: config.paths.inputs.clone(),
+            puzzles: config.paths.puzzles.clone(),
+        }
+    }
+
+    fn get(&self, path: &str) -> Result<Vec<u8>, FetchError> {
+        let invalid_url = || FetchError::InvalidUrl(self.url.clone());
+        let rest = self.url.strip_prefix("http://").ok_or_else(invalid_url)?;
+        let (host, base) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        if host.is_empty() {
+            return Err(invalid_url());
+        }
+        let address = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{host}:80")
+        };
+
+        let mut request = format!(
+            "GET {base}{path} HTTP/1.1\r\nHost: {host}\r\nUser-Agent: advent-of-code-rust template\r\nConnection: close\r\n"
+        );
+        if let Some(session) = &self.session {
+            request.push_str(&format!("Cookie: session={session}\r\n"));
+        }
+        request.push_str("\r\n");
+
+        let response = send(&address, request.as_bytes()).map_err(FetchError::Network)?;
+        parse_response(&response)
+    }
+}
+
+/// Sends a request and reads the response until the server closes the connection.
+fn send(address: &str, request: &[u8]) -> io::Result<Vec<u8>> {
+    let mut stream = connect(address.to_socket_addrs()?)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    stream.write_all(request)?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    Ok(response)
+}
+
+/// Connects to the first of the resolved addresses that accepts a connection within the timeout.
+fn connect(addresses: impl Iterator<Item = SocketAddr>) -> io::Result<TcpStream> {
+    let mut last_error = None;
+    for address in addresses {
+        match TcpStream::connect_timeout(&address, TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "the host did not resolve to an address",
+        )
+    }))
+}
+
+impl Fetch for HttpFetcher {
+    fn fetch(&mut self, day: Day) -> Result<(), FetchError> {
+        let n = day.into_inner();
+        let input = self.get(&format!("/{}/day/{n}/input", self.year))?;
+        let page = self.get(&format!("/{}/day/{n}", self.year))?;
+        let puzzle = articles(&String::from_utf8_lossy(&page));
+
+        let input_path = self.inputs.join(format!("{day}.txt"));
+        let puzzle_path = self.puzzles.join(format!("{day}.html"));
+        fs::create_dir_all(&self.inputs)?;
+        fs::create_dir_all(&self.puzzles)?;
+        fs::write(&input_path, input)?;
+        fs::write(&puzzle_path, puzzle)?;
+
+        println!(
+            "🎄 Successfully wrote input to \"{}\".",
+            input_path.display()
+        );
+        println!(
+            "🎄 Successfully wrote puzzle to \"{}\".",
+            puzzle_path.display()
+        );
+        Ok(())
+    }
+}
+
+/// Returns the body of a `200 OK` response, decoding a chunked transfer encoding.
+fn parse_response(response: &[u8]) -> Result<Vec<u8>, FetchError> {
+    let split = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or(FetchError::InvalidResponse)?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    let body = &response[split + 4..];
+
+    let mut lines = head.lines();
+    let status: u16 = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|status| status.parse().ok())
+        .ok_or(FetchError::InvalidResponse)?;
+    if status != 200 {
+        return Err(FetchError::Status(status));
+    }
+
+    let chunked = lines.any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.trim().eq_ignore_ascii_case("chunked")
+        })
+    });
+
+    if chunked {
+        decode_chunked(body)
+    } else {
+        Ok(body.to_vec())
+    }
+}
+
+fn decode_chunked(mut body: &[u8]) -> Result<Vec<u8>, FetchError> {
+    let mut decoded = Vec::new();
+    loop {
+        let end = body
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or(FetchError::InvalidResponse)?;
+        let size = String::from_utf8_lossy(&body[..end]);
+        let size = size.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16).map_err(|_| FetchError::InvalidResponse)?;
+        if size == 0 {
+            return Ok(decoded);
+        }
+        let chunk = body
+            .get(end + 2..end + 2 + size)
+            .ok_or(FetchError::InvalidResponse)?;
+        decoded.extend_from_slice(chunk);
+        body = body
+            .get(end + 4 + size..)
+            .ok_or(FetchError::InvalidResponse)?;
+    }
+}
+
+/// The `<article>` elements of a puzzle page, or the whole page if it has none.
+fn articles(page: &str) -> String {
+    let mut result = Vec::new();
+    let mut rest = page;
+    while let Some(start) = rest.find("<article") {
+        let Some(end) = rest[start..].find("</article>") else {
+            break;
+        };
+        let end = start + end + "</article>".len();
+        result.push(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    if result.is_empty() {
+        page.to_string()
+    } else {
+        result.join("\n")
+    }
+}
+
+#[cfg(feature = "test_lib")]
+pub(crate) mod tests {
+    use super::{articles, parse_response, Fetch, FetchError, HttpFetcher};
+    use crate::template::config::Config;
+    use crate::template::Day;
+    use std::io::{self, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+    use std::{env, fs, process};
+
+    /// Serves the given responses, one per connection, and returns the requests it received.
+    pub(crate) fn mock_server(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                requests.push(String::from_utf8(request).unwrap());
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    pub(crate) fn ok(body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    pub(crate) fn not_found() -> String {
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".into()
+    }
+
+    #[test]
+    fn parses_responses() {
+        assert_eq!(parse_response(ok("1\n2\n").as_bytes()).unwrap(), b"1\n2\n");
+        assert!(matches!(
+            parse_response(not_found().as_bytes()),
+            Err(FetchError::Status(404))
+        ));
+        assert!(matches!(
+            parse_response(b"HTTP/1.1 200 OK\r\n"),
+            Err(FetchError::InvalidResponse)
+        ));
+
+        let chunked =
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n1\n2\n\r\n3;x=y\r\n3\n4\r\n0\r\n\r\n";
+        assert_eq!(parse_response(chunked).unwrap(), b"1\n2\n3\n4");
+    }
+
+    #[test]
+    fn retries_network_errors_only() {
+        let error = || io::Error::new(io::ErrorKind::ConnectionRefused, "refused");
+        assert!(FetchError::Network(error()).is_retryable());
+        assert!(!FetchError::IO(error()).is_retryable());
+        assert!(FetchError::Status(404).is_retryable());
+        assert!(!FetchError::Status(400).is_retryable());
+    }
+
+    #[test]
+    fn does_not_retry_local_write_errors() {
+        let file = env::temp_dir().join(format!("aoc-fetch-{}", process::id()));
+        fs::write(&file, "not a directory").unwrap();
+        let (url, server) = mock_server(vec![ok("1\n"), ok("<article>puzzle</article>")]);
+
+        let mut config = Config::default();
+        config.paths.inputs = file.join("inputs");
+        let mut fetcher = HttpFetcher::new(&url, 2026, &config);
+
+        let result = fetcher.fetch(Day::new(1).unwrap());
+        assert!(matches!(&result, Err(e @ FetchError::IO(_)) if !e.is_retryable()));
+        assert_eq!(server.join().unwrap().len(), 2);
+        fs::remove_file(file).unwrap();
+    }
+
+    #[test]
+    fn reports_unreachable_servers_as_network_errors() {
+        // bind and drop a listener to find a port that refuses connections.
+        let address = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let mut fetcher = HttpFetcher::new(&format!("http://{address}"), 2026, &Config::default());
+
+        let result = fetcher.fetch(Day::new(1).unwrap());
+        assert!(matches!(result, Err(FetchError::Network(_))));
+    }
+
+    #[test]
+    fn extracts_articles() {
+        let page = "<main><article><h2>Day 1</h2></article><p>x</p><article>two</article></main>";
+        assert_eq!(
+            articles(page),
+            "<article><h2>Day 1</h2></article>\n<article>two</article>"
+        );
+        assert_eq!(articles("<p>none</p>"), "<p>none</p>");
+    }
+}
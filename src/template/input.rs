@@ -0,0 +1,203 @@
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::{env, fs, process};
+
+use crate::template::config::Config;
+use crate::template::Day;
+
+/// The input a solution binary runs against.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum InputSource {
+    /// The puzzle input, `NN.txt` in the inputs directory.
+    #[default]
+    Puzzle,
+    /// An arbitrary file, or stdin if the path is `-`.
+    File(PathBuf),
+    /// `NN.txt` in the examples directory, or `NN-N.txt` if a number is given.
+    Example(Option<u8>),
+    /// Every file in a directory, compared side by side. See [`read_input_dir`].
+    Directory(PathBuf),
+}
+
+impl InputSource {
+    /// Reads the input source from the arguments of a solution binary, i.e. `--input <PATH>` or `--example [N]`.
+    pub fn from_args(args: &[String]) -> Self {
+        let value_of = |name: &str| {
+            let index = args.iter().position(|arg| arg == name)?;
+            Some(args.get(index + 1).filter(|value| !value.starts_with("--")))
+        };
+
+        if let Some(dir) = value_of("--inputs") {
+            let Some(dir) = dir else {
+                eprintln!("Unexpected command-line input. Format: cargo solve 1 --inputs <DIR>");
+                process::exit(1);
+            };
+            return InputSource::Directory(dir.into());
+        }
+
+        if let Some(path) = value_of("--input") {
+            let Some(path) = path else {
+                eprintln!("Unexpected command-line input. Format: cargo solve 1 --input <PATH>");
+                process::exit(1);
+            };
+            return InputSource::File(path.into());
+        }
+
+        if let Some(n) = value_of("--example") {
+            let n = n.map(|n| {
+                n.parse().unwrap_or_else(|_| {
+                    eprintln!("Unexpected command-line input. Format: cargo solve 1 --example 2");
+                    process::exit(1);
+                })
+            });
+            return InputSource::Example(n);
+        }
+
+        InputSource::Puzzle
+    }
+
+    /// The arguments that select this input source when passed to a solution binary.
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            InputSource::Puzzle => vec![],
+            InputSource::File(path) => vec!["--input".into(), path.to_string_lossy().into()],
+            InputSource::Example(None) => vec!["--example".into()],
+            InputSource::Example(Some(n)) => vec!["--example".into(), n.to_string()],
+            InputSource::Directory(dir) => vec!["--inputs".into(), dir.to_string_lossy().into()],
+        }
+    }
+
+    /// Reads the input for the given day. Exits the process if it can not be read.
+    pub fn read(&self, day: Day) -> String {
+        let config = Config::get();
+
+        let (path, result) = match self {
+            InputSource::Directory(dir) => {
+                let error = io::Error::new(io::ErrorKind::InvalidInput, "expected a single file");
+                (dir.clone(), Err(error))
+            }
+            InputSource::File(path) if path.as_os_str() == "-" => {
+                let mut input = String::new();
+                let result = io::stdin().read_to_string(&mut input).map(|_| input);
+                ("stdin".into(), result)
+            }
+            InputSource::File(path) => (path.clone(), fs::read_to_string(path)),
+            InputSource::Puzzle | InputSource::Example(_) => {
+                let path = match self {
+                    InputSource::Example(Some(n)) => {
+                        config.paths.examples.join(format!("{day}-{n}.txt"))
+                    }
+                    InputSource::Example(None) => config.paths.examples.join(format!("{day}.txt")),
+                    _ => config.paths.inputs.join(format!("{day}.txt")),
+                };
+                let result = fs::read_to_string(&path);
+                (path, result)
+            }
+        };
+
+        result.unwrap_or_else(|e| {
+            eprintln!("Could not read input from \"{}\": {e}", path.display());
+            process::exit(1);
+        })
+    }
+}
+
+/// Reads the input of a solution binary, as selected by its arguments. See [`InputSource`].
+#[must_use]
+pub fn read_input(day: Day) -> String {
+    let args: Vec<String> = env::args().collect();
+    InputSource::from_args(&args).read(day)
+}
+
+/// Reads every file in the directory passed with `--inputs <DIR>`, sorted by file name.
+/// Returns [`None`] if no directory was passed. Exits the process if the directory can not be read.
+#[must_use]
+pub fn read_input_dir() -> Option<Vec<(String, String)>> {
+    let args: Vec<String> = env::args().collect();
+    let InputSource::Directory(dir) = InputSource::from_args(&args) else {
+        return None;
+    };
+
+    let exit = |e: io::Error| -> ! {
+        eprintln!("Could not read inputs from \"{}\": {e}", dir.display());
+        process::exit(1);
+    };
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|e| exit(e))
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && !path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    paths.sort();
+
+    if paths.is_empty() {
+        eprintln!("No input files found in \"{}\".", dir.display());
+        process::exit(1);
+    }
+
+    let inputs = paths
+        .into_iter()
+        .map(|path| {
+            let input = fs::read_to_string(&path).unwrap_or_else(|e| exit(e));
+            let name = path.file_name().unwrap().to_string_lossy().into();
+            (name, input)
+        })
+        .collect();
+
+    Some(inputs)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::InputSource;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_input_source() {
+        assert_eq!(
+            InputSource::from_args(&args("01 --time")),
+            InputSource::Puzzle
+        );
+        assert_eq!(
+            InputSource::from_args(&args("01 --input a/b.txt --time")),
+            InputSource::File("a/b.txt".into())
+        );
+        assert_eq!(
+            InputSource::from_args(&args("01 --input -")),
+            InputSource::File("-".into())
+        );
+        assert_eq!(
+            InputSource::from_args(&args("01 --example --time")),
+            InputSource::Example(None)
+        );
+        assert_eq!(
+            InputSource::from_args(&args("01 --example 2")),
+            InputSource::Example(Some(2))
+        );
+    }
+
+    #[test]
+    fn round_trips_args() {
+        for source in [
+            InputSource::Puzzle,
+            InputSource::File("-".into()),
+            InputSource::Example(None),
+            InputSource::Example(Some(3)),
+            InputSource::Directory("inputs/".into()),
+        ] {
+            let mut args = vec!["01".to_string()];
+            args.extend(source.to_args());
+            assert_eq!(InputSource::from_args(&args), source);
+        }
+    }
+}
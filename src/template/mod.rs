@@ -1,12 +1,29 @@
-use std::{env, fs};
+use std::fs;
 
+use config::{Config, OutputFormat};
+
+pub mod alloc;
 pub mod aoc_cli;
+pub mod cli;
 pub mod commands;
+pub mod config;
+#[cfg(feature = "today")]
+pub mod fetch;
 pub mod runner;
+pub mod testing;
+#[cfg(feature = "today")]
+pub mod unlock;
 
 pub use day::*;
+pub use day_set::*;
+pub use input::{read_input, read_input_dir, InputSource};
+pub use run_multi::Limits;
 
 mod day;
+mod day_set;
+mod input;
+mod panics;
+mod perf;
 mod readme_benchmarks;
 mod run_multi;
 mod timings;
@@ -15,11 +32,19 @@ pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
+/// Returns the given escape code, or an empty string if plain output is configured.
+#[must_use]
+pub fn ansi(code: &'static str) -> &'static str {
+    match Config::get().output.format {
+        OutputFormat::Pretty => code,
+        OutputFormat::Plain => "",
+    }
+}
+
 /// Helper function that reads a text file to a string.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = Config::get().data_dir(folder).join(format!("{day}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
@@ -27,10 +52,8 @@ pub fn read_file(folder: &str, day: Day) -> String {
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
+    let filepath = Config::get()
+        .data_dir(folder)
         .join(format!("{day}-{part}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
@@ -59,9 +82,24 @@ macro_rules! solution {
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
+        #[cfg(all(feature = "count-allocs", not(feature = "dhat-heap")))]
+        #[global_allocator]
+        static ALLOC: $crate::template::alloc::CountingAlloc = $crate::template::alloc::CountingAlloc;
+
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
+
+            if let Some(inputs) = $crate::template::read_input_dir() {
+                let inputs: Vec<(String, &str)> = inputs
+                    .iter()
+                    .map(|(name, input)| (name.clone(), input.as_str()))
+                    .collect();
+                let parts = vec![$( ($part, compare_part($func, &inputs)), )*];
+                print_comparison(&inputs, &parts);
+                return;
+            }
+
+            let input = $crate::template::read_input(DAY);
             $( run_part($func, &input, DAY, $part); )*
         }
     };
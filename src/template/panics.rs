@@ -0,0 +1,91 @@
+/// Catches panics of solution functions for commands that report them on their own, e.g. input comparisons and
+/// property tests. A single panic hook is installed once per process. It skips printing while the panicking thread
+/// is silenced, so tests running in parallel keep reporting their own panics.
+use std::any::Any;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+thread_local! {
+    static SILENCED: Cell<bool> = const { Cell::new(false) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Resets the silenced flag of the current thread, also when the silenced function unwinds.
+struct Restore(bool);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        SILENCED.with(|silenced| silenced.set(self.0));
+    }
+}
+
+/// Runs `func` without printing the messages of panics on the current thread.
+pub fn silenced<T>(func: impl FnOnce() -> T) -> T {
+    INSTALL_HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !SILENCED.with(Cell::get) {
+                hook(info);
+            }
+        }));
+    });
+
+    let _restore = Restore(SILENCED.with(|silenced| silenced.replace(true)));
+    func()
+}
+
+/// Calls `func`, returning the panic message if it panics.
+pub fn catch<T>(func: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(func)).map_err(|payload| message(payload.as_ref()))
+}
+
+fn message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic payload".into())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{catch, silenced, SILENCED};
+    use std::cell::Cell;
+
+    #[test]
+    fn catches_panic_messages() {
+        assert_eq!(silenced(|| catch(|| 1)), Ok(1));
+        assert_eq!(
+            silenced(|| catch(|| -> u8 { panic!("static") })),
+            Err("static".into())
+        );
+        assert_eq!(
+            silenced(|| catch(|| -> u8 { panic!("formatted {}", 1) })),
+            Err("formatted 1".into())
+        );
+    }
+
+    #[test]
+    fn silences_only_while_running() {
+        let inner = silenced(|| {
+            silenced(|| {});
+            SILENCED.with(Cell::get)
+        });
+
+        assert!(inner);
+        assert!(!SILENCED.with(Cell::get));
+
+        let _ = catch(|| silenced(|| panic!("unwinds through silenced")));
+        assert!(!SILENCED.with(Cell::get));
+    }
+
+    #[test]
+    fn does_not_silence_other_threads() {
+        silenced(|| {
+            let other = std::thread::spawn(|| SILENCED.with(Cell::get)).join().unwrap();
+            assert!(!other);
+        });
+    }
+}
@@ -0,0 +1,362 @@
+/// Reads hardware performance counters via `perf_event_open` on Linux.
+/// On other platforms, or if the kernel does not permit access to the counters, opening them fails with an error.
+use std::fmt::Display;
+
+use crate::template::timings::CounterStats;
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum PerfError {
+    Unsupported,
+    Open(std::io::Error),
+    Read(std::io::Error),
+}
+
+impl Display for PerfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PerfError::Unsupported => {
+                write!(f, "hardware counters are only supported on Linux (x86_64, aarch64).")
+            }
+            PerfError::Open(e) => write!(
+                f,
+                "could not open hardware counters ({e}). Check `/proc/sys/kernel/perf_event_paranoid`."
+            ),
+            PerfError::Read(e) => write!(f, "could not read hardware counters ({e})."),
+        }
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod sys {
+    use std::fs::File;
+    use std::io::{self, Read};
+    use std::os::fd::{AsRawFd, FromRawFd};
+    use std::os::raw::{c_int, c_long, c_ulong};
+
+    use super::{CounterGroup, PerfError};
+    use crate::template::timings::CounterStats;
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_PERF_EVENT_OPEN: c_long = 298;
+    #[cfg(target_arch = "aarch64")]
+    const SYS_PERF_EVENT_OPEN: c_long = 241;
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_COUNT_HW_BRANCH_MISSES: u64 = 5;
+
+    const PERF_FORMAT_GROUP: u64 = 1 << 3;
+
+    const FLAG_DISABLED: u64 = 1;
+    const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+    const PERF_EVENT_IOC_ENABLE: c_ulong = 0x2400;
+    const PERF_EVENT_IOC_DISABLE: c_ulong = 0x2401;
+    const PERF_EVENT_IOC_RESET: c_ulong = 0x2403;
+    const PERF_IOC_FLAG_GROUP: c_ulong = 1;
+
+    /// Matches `struct perf_event_attr` as of `PERF_ATTR_SIZE_VER0`.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        type_: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    extern "C" {
+        fn syscall(num: c_long, ...) -> c_long;
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    // NOTE: the order of events determines the order of values in a group read.
+    const EVENTS: [u64; 4] = [
+        PERF_COUNT_HW_INSTRUCTIONS,
+        PERF_COUNT_HW_CPU_CYCLES,
+        PERF_COUNT_HW_CACHE_MISSES,
+        PERF_COUNT_HW_BRANCH_MISSES,
+    ];
+
+    /// A group of hardware counters for the current thread. The first event is the group leader.
+    pub struct Counters {
+        files: Vec<File>,
+    }
+
+    fn open_event(config: u64, group_fd: c_int) -> Result<File, PerfError> {
+        let attr = PerfEventAttr {
+            type_: PERF_TYPE_HARDWARE,
+            #[allow(clippy::cast_possible_truncation)]
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            read_format: PERF_FORMAT_GROUP,
+            // only the leader starts disabled, members follow the state of the group.
+            flags: if group_fd == -1 { FLAG_DISABLED } else { 0 }
+                | FLAG_EXCLUDE_KERNEL
+                | FLAG_EXCLUDE_HV,
+            ..Default::default()
+        };
+
+        // SAFETY: `attr` is a valid `perf_event_attr` that outlives the call.
+        let fd = unsafe {
+            syscall(
+                SYS_PERF_EVENT_OPEN,
+                std::ptr::from_ref(&attr),
+                0 as c_int,
+                -1 as c_int,
+                group_fd,
+                0 as c_ulong,
+            )
+        };
+
+        if fd < 0 {
+            return Err(PerfError::Open(io::Error::last_os_error()));
+        }
+
+        // SAFETY: the file descriptor was just opened and is exclusively owned by the returned file.
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(unsafe { File::from_raw_fd(fd as c_int) })
+    }
+
+    impl Counters {
+        pub fn open() -> Result<Self, PerfError> {
+            let mut files: Vec<File> = Vec::with_capacity(EVENTS.len());
+
+            for config in EVENTS {
+                let group_fd = files.first().map_or(-1, AsRawFd::as_raw_fd);
+                files.push(open_event(config, group_fd)?);
+            }
+
+            Ok(Self { files })
+        }
+
+        fn group_ioctl(&self, request: c_ulong) {
+            // SAFETY: the leader file descriptor is valid for the lifetime of `self`.
+            unsafe {
+                ioctl(self.files[0].as_raw_fd(), request, PERF_IOC_FLAG_GROUP);
+            }
+        }
+    }
+
+    impl CounterGroup for Counters {
+        fn start(&self) {
+            self.group_ioctl(PERF_EVENT_IOC_RESET);
+            self.group_ioctl(PERF_EVENT_IOC_ENABLE);
+        }
+
+        fn stop(&self) {
+            self.group_ioctl(PERF_EVENT_IOC_DISABLE);
+        }
+
+        fn read(&self) -> Result<CounterStats, PerfError> {
+            // format: { nr, values[nr] }
+            let mut buf = [0_u8; 8 * (EVENTS.len() + 1)];
+            (&self.files[0])
+                .read_exact(&mut buf)
+                .map_err(PerfError::Read)?;
+
+            let value = |i: usize| {
+                let start = 8 * (i + 1);
+                u64::from_ne_bytes(buf[start..start + 8].try_into().unwrap())
+            };
+
+            Ok(CounterStats {
+                instructions: value(0),
+                cycles: value(1),
+                cache_misses: value(2),
+                branch_misses: value(3),
+            })
+        }
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+mod sys {
+    use super::{CounterGroup, PerfError};
+    use crate::template::timings::CounterStats;
+
+    pub struct Counters;
+
+    impl Counters {
+        pub fn open() -> Result<Self, PerfError> {
+            Err(PerfError::Unsupported)
+        }
+    }
+
+    impl CounterGroup for Counters {
+        fn start(&self) {}
+
+        fn stop(&self) {}
+
+        fn read(&self) -> Result<CounterStats, PerfError> {
+            Err(PerfError::Unsupported)
+        }
+    }
+}
+
+/// A group of counters that is enabled around a measured section and read afterwards.
+pub trait CounterGroup {
+    fn start(&self);
+    fn stop(&self);
+    fn read(&self) -> Result<CounterStats, PerfError>;
+}
+
+/// Hardware counters that accumulate measurements over several samples.
+pub struct PerfCounters<C = sys::Counters> {
+    counters: C,
+    total: CounterStats,
+    samples: u64,
+}
+
+impl PerfCounters {
+    pub fn new() -> Result<Self, PerfError> {
+        Ok(Self::with_counters(sys::Counters::open()?))
+    }
+}
+
+impl<C: CounterGroup> PerfCounters<C> {
+    fn with_counters(counters: C) -> Self {
+        Self {
+            counters,
+            total: CounterStats::default(),
+            samples: 0,
+        }
+    }
+
+    /// Measures the counters while executing `func`, adding the result to the running total.
+    /// The value of `func` is returned even if the counters could not be read, so callers can keep their own timings.
+    pub fn sample<T>(&mut self, func: impl FnOnce() -> T) -> (T, Result<(), PerfError>) {
+        self.counters.start();
+        let result = func();
+        self.counters.stop();
+
+        let stats = match self.counters.read() {
+            Ok(stats) => stats,
+            Err(e) => return (result, Err(e)),
+        };
+
+        self.total.instructions += stats.instructions;
+        self.total.cycles += stats.cycles;
+        self.total.cache_misses += stats.cache_misses;
+        self.total.branch_misses += stats.branch_misses;
+        self.samples += 1;
+
+        (result, Ok(()))
+    }
+
+    /// Returns the average counter values per sample.
+    pub fn average(&self) -> CounterStats {
+        let samples = self.samples.max(1);
+        CounterStats {
+            instructions: self.total.instructions / samples,
+            cycles: self.total.cycles / samples,
+            cache_misses: self.total.cache_misses / samples,
+            branch_misses: self.total.branch_misses / samples,
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::cell::Cell;
+
+    use super::{CounterGroup, PerfCounters, PerfError};
+    use crate::template::timings::CounterStats;
+
+    /// Counts a fixed number of instructions per sample and fails to read once `fail_after` samples were taken.
+    struct FakeCounters {
+        reads: Cell<u64>,
+        fail_after: u64,
+    }
+
+    impl CounterGroup for FakeCounters {
+        fn start(&self) {}
+
+        fn stop(&self) {}
+
+        fn read(&self) -> Result<CounterStats, PerfError> {
+            let reads = self.reads.get() + 1;
+            self.reads.set(reads);
+
+            if reads > self.fail_after {
+                return Err(PerfError::Unsupported);
+            }
+
+            Ok(CounterStats {
+                instructions: 100 * reads,
+                cycles: 10,
+                cache_misses: reads,
+                branch_misses: 0,
+            })
+        }
+    }
+
+    fn fake(fail_after: u64) -> PerfCounters<FakeCounters> {
+        PerfCounters::with_counters(FakeCounters {
+            reads: Cell::new(0),
+            fail_after,
+        })
+    }
+
+    #[test]
+    fn averages_samples() {
+        let mut perf = fake(u64::MAX);
+        for _ in 0..4 {
+            assert!(perf.sample(|| ()).1.is_ok());
+        }
+
+        assert_eq!(
+            perf.average(),
+            CounterStats {
+                instructions: 250,
+                cycles: 10,
+                cache_misses: 2,
+                branch_misses: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn averages_nothing_without_samples() {
+        assert_eq!(fake(0).average(), CounterStats::default());
+    }
+
+    #[test]
+    fn returns_result_when_counters_fail() {
+        let mut perf = fake(1);
+
+        assert_eq!(perf.sample(|| 1).0, 1);
+
+        let (result, read) = perf.sample(|| 2);
+        assert_eq!(result, 2);
+        assert!(matches!(read, Err(PerfError::Unsupported)));
+
+        // failed samples do not count towards the average.
+        assert_eq!(perf.average().instructions, 100);
+    }
+
+    #[test]
+    fn opens_counters_or_reports_why_not() {
+        // counters are commonly not permitted in containers, which the runner treats as "no counters".
+        match PerfCounters::new() {
+            Ok(mut perf) => assert_eq!(perf.sample(|| 3).0, 3),
+            Err(e) => assert!(matches!(e, PerfError::Open(_) | PerfError::Unsupported)),
+        }
+    }
+}
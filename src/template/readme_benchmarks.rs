@@ -2,10 +2,13 @@
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
 use std::{fs, io};
 
-use crate::template::timings::Timings;
+use crate::template::config::Config;
+use crate::template::timings::{MemoryStats, Timings};
 use crate::template::Day;
 
-static MARKER: &str = "<!--- benchmarking table --->";
+fn marker() -> &'static str {
+    &Config::get().readme.marker
+}
 
 #[allow(dead_code)]
 #[derive(Debug)]
@@ -31,7 +34,7 @@ pub fn get_path_for_bin(day: Day) -> String {
 }
 
 fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+    let matches: Vec<_> = readme.match_indices(marker()).collect();
 
     if matches.len() > 2 {
         return Err(Error::Parser(
@@ -52,31 +55,82 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+fn format_memory(memory: Option<MemoryStats>) -> String {
+    memory.map_or_else(
+        || "-".into(),
+        |m| {
+            format!(
+                "`{}` / `{}` / `{}`",
+                format_bytes(m.peak_bytes),
+                format_bytes(m.total_bytes),
+                m.allocations
+            )
+        },
+    )
+}
+
 fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
     let header = format!("{prefix} Benchmarks");
+    let has_memory = timings.has_memory();
 
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
-    ];
+    let mut lines: Vec<String> = vec![marker().into(), header, String::new()];
+
+    if has_memory {
+        lines.push("| Day | Part 1 | Part 2 | Memory 1 | Memory 2 |".into());
+        lines.push("| :---: | :---: | :---: | :---: | :---:  |".into());
+    } else {
+        lines.push("| Day | Part 1 | Part 2 |".into());
+        lines.push("| :---: | :---: | :---:  |".into());
+    }
 
     for timing in timings.data {
         let path = get_path_for_bin(timing.day);
-        lines.push(format!(
+        // parts that did not finish because the run was aborted show the reason instead.
+        let missing = timing.aborted.map_or_else(|| "-".into(), |x| x.to_string());
+        let mut line = format!(
             "| [Day {}]({}) | `{}` | `{}` |",
             timing.day.into_inner(),
             path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
-        ));
+            timing.part_1.unwrap_or_else(|| missing.clone()),
+            timing.part_2.unwrap_or(missing)
+        );
+
+        if has_memory {
+            line.push_str(&format!(
+                " {} | {} |",
+                format_memory(timing.part_1_memory),
+                format_memory(timing.part_2_memory)
+            ));
+        }
+
+        lines.push(line);
     }
 
     lines.push(String::new());
+    if has_memory {
+        lines.push("_Memory: peak heap / total allocated / allocation count._".into());
+        lines.push(String::new());
+    }
     lines.push(format!("**Total: {total_millis:.2}ms**"));
-    lines.push(MARKER.into());
+    lines.push(marker().into());
 
     lines.join("\n")
 }
@@ -89,7 +143,7 @@ fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result
 }
 
 pub fn update(timings: Timings) -> Result<(), Error> {
-    let path = "README.md";
+    let path = &Config::get().readme.path;
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
     let total_millis = timings.total_millis();
     update_content(&mut readme, timings, total_millis)?;
@@ -99,8 +153,11 @@ pub fn update(timings: Timings) -> Result<(), Error> {
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use super::{marker, update_content};
+    use crate::{
+        day,
+        template::timings::{Abort, MemoryStats, Timing, Timings},
+    };
 
     fn get_mock_timings() -> Timings {
         Timings {
@@ -109,18 +166,33 @@ mod tests {
                     day: day!(1),
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
+                    aborted: None,
                     total_nanos: 3e+10,
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
+                    aborted: None,
                     total_nanos: 7e+10,
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: Some("50ms".into()),
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
+                    aborted: None,
                     total_nanos: 9e+10,
                 },
             ],
@@ -137,29 +209,29 @@ mod tests {
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
-        let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
+        let mut s = format!("{} {} {}", marker(), marker(), marker());
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
-        let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
+        let mut s = format!("foo\nbar\n{}{}\nbaz", marker(), marker());
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
-        let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
+        let mut s = format!("foo\nbar\n{}{}\nbaz", marker(), marker());
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
+        assert_eq!(s.matches(marker()).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
 
     #[test]
     fn format_benchmarks() {
-        let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
+        let mut s = format!("foo\nbar\n{}\n{}\nbaz", marker(), marker());
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
         let expected = [
             "foo",
@@ -180,4 +252,47 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_benchmarks_with_memory() {
+        let mut timings = get_mock_timings();
+        timings.data[0].part_1_memory = Some(MemoryStats {
+            peak_bytes: 512,
+            total_bytes: 3 * 1024 * 1024,
+            allocations: 12,
+        });
+
+        let mut s = format!("{}\n{}", marker(), marker());
+        update_content(&mut s, timings, 190.0).unwrap();
+
+        assert_eq!(
+            s.contains("| Day | Part 1 | Part 2 | Memory 1 | Memory 2 |"),
+            true
+        );
+        assert_eq!(
+            s.contains(
+                "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | `512 B` / `3.0 MiB` / `12` | - |"
+            ),
+            true
+        );
+        assert_eq!(
+            s.contains("| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` | - | - |"),
+            true
+        );
+    }
+
+    #[test]
+    fn format_aborted_benchmarks() {
+        let mut timings = get_mock_timings();
+        timings.data[2].part_2 = None;
+        timings.data[2].aborted = Some(Abort::Timeout);
+
+        let mut s = format!("{}\n{}", marker(), marker());
+        update_content(&mut s, timings, 190.0).unwrap();
+
+        assert_eq!(
+            s.contains("| [Day 4](./src/bin/04.rs) | `40ms` | `timed out` |"),
+            true
+        );
+    }
 }
@@ -1,44 +1,87 @@
-use std::{collections::HashSet, io};
+use std::{io, time::Duration};
 
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{ansi, Day, DaySet, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
-use super::{
-    all_days,
-    timings::{Timing, Timings},
-};
+use super::timings::{Timing, Timings};
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+/// Limits that abort a solution run when exceeded.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Limits {
+    /// Maximum wall-clock time for running all parts of a day.
+    pub day_timeout: Option<Duration>,
+    /// Maximum wall-clock time for running a single part.
+    pub part_timeout: Option<Duration>,
+    /// Maximum resident memory of a solution in bytes. Only supported on Linux.
+    pub memory_limit: Option<u64>,
+}
+
+impl Limits {
+    pub fn is_set(&self) -> bool {
+        self.day_timeout.is_some() || self.part_timeout.is_some() || self.memory_limit.is_some()
+    }
+}
+
+/// Options that control how solutions are invoked by [`run_multi`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RunOptions {
+    pub is_release: bool,
+    pub is_timed: bool,
+    pub is_memory: bool,
+    pub is_perf: bool,
+    pub limits: Limits,
+}
+
+pub fn run_multi(days_to_run: &DaySet, options: &RunOptions) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
 
     let mut need_space = false;
 
-    // NOTE: use non-duplicate, sorted day values.
-    all_days()
-        .filter(|day| days_to_run.contains(day))
-        .for_each(|day| {
-            if need_space {
-                println!();
-            }
-            need_space = true;
+    if days_to_run.is_empty() {
+        println!("No days match the selection.");
+    }
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
+    // NOTE: day sets iterate non-duplicate, sorted day values.
+    days_to_run.iter().for_each(|day| {
+        if need_space {
+            println!();
+        }
+        need_space = true;
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+        println!("{}Day {day}{}", ansi(ANSI_BOLD), ansi(ANSI_RESET));
+        println!("------");
 
-            if output.is_empty() {
-                println!("Not solved.");
-            } else {
-                let val = child_commands::parse_exec_time(&output, day);
-                timings.push(val);
+        let run = child_commands::run_solution(day, options).unwrap();
+
+        if let Some(abort) = run.aborted {
+            println!("{}✖ {abort}{}", ansi(ANSI_BOLD), ansi(ANSI_RESET));
+        }
+
+        if run.output.is_empty() && run.aborted.is_none() {
+            println!("Not solved.");
+        } else {
+            let mut val = child_commands::parse_exec_time(&run.output, day);
+            child_commands::parse_counters(&run.output, &mut val);
+            val.aborted = run.aborted;
+
+            if options.is_memory && run.aborted.is_none() {
+                // heap profiling slows down execution, run it separately from the timed run.
+                let run = child_commands::run_memory_profile(day, &options.limits).unwrap();
+                child_commands::parse_memory(&run.output, &mut val);
             }
-        });
 
-    if is_timed {
+            timings.push(val);
+        }
+    });
+
+    if options.is_timed {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
         println!(
-            "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
+            "\n{}Total (Run):{} {}{total_millis:.2}ms{}",
+            ansi(ANSI_BOLD),
+            ansi(ANSI_RESET),
+            ansi(ANSI_ITALIC),
+            ansi(ANSI_RESET)
         );
         Some(timings)
     } else {
@@ -59,6 +102,8 @@ impl From<std::io::Error> for Error {
     }
 }
 
+/// The source file of a day's solution. Unlike the data paths, this is not configurable: cargo only discovers
+/// binaries in `src/bin` without listing each of them in `Cargo.toml`.
 #[must_use]
 pub fn get_path_for_bin(day: Day) -> String {
     format!("./src/bin/{day}.rs")
@@ -67,65 +112,212 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::{get_path_for_bin, Error, Limits, RunOptions};
+    use crate::template::{
+        config::Config,
+        timings::{Abort, CounterStats, MemoryStats},
+        Day,
+    };
     use std::{
         io::{BufRead, BufReader},
         path::Path,
-        process::{Command, Stdio},
+        process::{Child, Command, Stdio},
+        sync::mpsc::{self, RecvTimeoutError},
         thread,
+        time::{Duration, Instant},
     };
 
+    /// How often limits are checked while a solution is running.
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// The stdout lines of a solution run, and the reason it was aborted, if any.
+    pub struct RunOutput {
+        pub output: Vec<String>,
+        pub aborted: Option<Abort>,
+    }
+
     /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    pub fn run_solution(day: Day, options: &RunOptions) -> Result<RunOutput, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+            return Ok(RunOutput {
+                output: vec![],
+                aborted: None,
+            });
         }
 
         let day_padded = day.to_string();
         let mut args = vec!["run", "--quiet", "--bin", &day_padded];
 
-        if is_release {
+        if options.is_release {
             args.push("--release");
         }
 
-        if is_timed {
+        if options.is_timed {
+            // count allocations while benching, the same way `--dhat` enables the heap profiler.
+            args.extend(["--features", "count-allocs"]);
+
             // mirror `--time` flag to child invocations.
             args.push("--");
             args.push("--time");
+
+            if options.is_perf {
+                args.push("--perf");
+            }
+        }
+
+        run_command(&args, &options.limits)
+    }
+
+    /// Run the solution bin for a given day with dhat enabled, collecting heap statistics for each part.
+    pub fn run_memory_profile(day: Day, limits: &Limits) -> Result<RunOutput, Error> {
+        let day_padded = day.to_string();
+        let args = vec![
+            "run",
+            "--quiet",
+            "--profile",
+            "dhat",
+            "--features",
+            "dhat-heap",
+            "--bin",
+            &day_padded,
+            "--",
+            "--memory",
+        ];
+
+        run_command(&args, limits)
+    }
+
+    fn run_command(args: &[&str], limits: &Limits) -> Result<RunOutput, Error> {
+        if limits.is_set() {
+            // compile ahead of time, so that build times do not count towards the limits.
+            let build_args: Vec<&str> = std::iter::once("build")
+                .chain(args[1..].iter().copied().take_while(|arg| *arg != "--"))
+                .collect();
+            Command::new("cargo")
+                .args(&build_args)
+                .envs(Config::get().env_vars())
+                .status()?;
         }
 
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
 
-        let mut cmd = Command::new("cargo")
-            .args(&args)
+        let cmd = Command::new("cargo")
+            .args(args)
+            .envs(Config::get().env_vars())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()?;
 
+        supervise(cmd, limits)
+    }
+
+    /// Waits for a child with piped output to exit, killing it once it exceeds the limits.
+    fn supervise(mut cmd: Child, limits: &Limits) -> Result<RunOutput, Error> {
         let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
         let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
 
         let mut output = vec![];
 
-        let thread = thread::spawn(move || {
+        let stderr_thread = thread::spawn(move || {
             stderr.lines().for_each(|line| {
                 eprintln!("{}", line.unwrap());
             });
         });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
-        }
+        // read stdout on a separate thread, so the limits can be checked while waiting for output.
+        let (tx, rx) = mpsc::channel();
+        let stdout_thread = thread::spawn(move || {
+            for line in stdout.lines() {
+                if tx.send(line.unwrap()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let day_start = Instant::now();
+        let mut part_start = day_start;
+
+        let aborted = loop {
+            match rx.recv_timeout(POLL_INTERVAL) {
+                Ok(line) => {
+                    println!("{line}");
+                    // a part prints its line once it has completed, the next part starts right after.
+                    if line.contains("Part ") {
+                        part_start = Instant::now();
+                    }
+                    output.push(line);
+                }
+                Err(RecvTimeoutError::Disconnected) => break None,
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+
+            let (day_elapsed, part_elapsed) = (day_start.elapsed(), part_start.elapsed());
+            let pid = cmd.id();
+            if let Some(abort) =
+                check_limits(limits, day_elapsed, part_elapsed, || resident_bytes(pid))
+            {
+                // NOTE: `cargo run` replaces itself with the solution binary on unix, so this kills the solution.
+                // on other platforms, only cargo is killed and the solution keeps running until it exits.
+                cmd.kill()?;
+                break Some(abort);
+            }
+        };
 
-        thread.join().unwrap();
+        stdout_thread.join().unwrap();
+        stderr_thread.join().unwrap();
         cmd.wait()?;
 
-        Ok(output)
+        Ok(RunOutput { output, aborted })
+    }
+
+    /// Returns the limit exceeded by a run, if any. The resident memory is only read if a memory limit is set.
+    fn check_limits(
+        limits: &Limits,
+        day_elapsed: Duration,
+        part_elapsed: Duration,
+        resident_bytes: impl FnOnce() -> Option<u64>,
+    ) -> Option<Abort> {
+        if let Some(timeout) = limits.day_timeout {
+            if day_elapsed > timeout {
+                return Some(Abort::Timeout);
+            }
+        }
+
+        if let Some(timeout) = limits.part_timeout {
+            if part_elapsed > timeout {
+                return Some(Abort::Timeout);
+            }
+        }
+
+        if let Some(memory_limit) = limits.memory_limit {
+            if resident_bytes().is_some_and(|bytes| bytes > memory_limit) {
+                return Some(Abort::MemoryLimit);
+            }
+        }
+
+        None
+    }
+
+    /// Reads the resident set size of a process from procfs.
+    #[cfg(target_os = "linux")]
+    fn resident_bytes(pid: u32) -> Option<u64> {
+        let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+        let kilobytes: u64 = status
+            .lines()
+            .find_map(|l| l.strip_prefix("VmRSS:"))?
+            .trim()
+            .strip_suffix("kB")?
+            .trim()
+            .parse()
+            .ok()?;
+        Some(kilobytes * 1024)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn resident_bytes(_pid: u32) -> Option<u64> {
+        None
     }
 
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
@@ -133,6 +325,11 @@ pub mod child_commands {
             day,
             part_1: None,
             part_2: None,
+            part_1_memory: None,
+            part_2_memory: None,
+            part_1_counters: None,
+            part_2_counters: None,
+            aborted: None,
             total_nanos: 0_f64,
         };
 
@@ -164,6 +361,79 @@ pub mod child_commands {
         timings
     }
 
+    pub fn parse_memory(output: &[String], timing: &mut super::Timing) {
+        for (part, values) in parse_stats(output, "peak") {
+            let [peak_bytes, total_bytes, allocations] = values[..] else {
+                continue;
+            };
+
+            let memory = Some(MemoryStats {
+                peak_bytes,
+                total_bytes,
+                allocations,
+            });
+
+            match part {
+                1 => timing.part_1_memory = memory,
+                2 => timing.part_2_memory = memory,
+                _ => {}
+            }
+        }
+    }
+
+    pub fn parse_counters(output: &[String], timing: &mut super::Timing) {
+        for (part, values) in parse_stats(output, "instructions") {
+            let [instructions, cycles, cache_misses, branch_misses] = values[..] else {
+                continue;
+            };
+
+            let counters = Some(CounterStats {
+                instructions,
+                cycles,
+                cache_misses,
+                branch_misses,
+            });
+
+            match part {
+                1 => timing.part_1_counters = counters,
+                2 => timing.part_2_counters = counters,
+                _ => {}
+            }
+        }
+    }
+
+    /// Parses bracketed stats starting with `key`, e.g. `[peak: 1024 B, total: 4096 B, allocations: 12]`.
+    /// Returns the part number and the numeric values of each matching line.
+    fn parse_stats<'a>(
+        output: &'a [String],
+        key: &'a str,
+    ) -> impl Iterator<Item = (u8, Vec<u64>)> + 'a {
+        let marker = format!(" [{key}: ");
+
+        output.iter().filter_map(move |l| {
+            let start = l.find(&marker)? + 2;
+            let end = start + l[start..].find(']')?;
+
+            let Some(values) = l[start..end]
+                .split(", ")
+                .map(|s| s.rsplit(": ").next()?.trim_end_matches(" B").parse().ok())
+                .collect::<Option<Vec<u64>>>()
+            else {
+                eprintln!("Could not parse stats from line: {l}");
+                return None;
+            };
+
+            let part = l.split(':').next()?;
+            if part.contains("Part 1") {
+                Some((1, values))
+            } else if part.contains("Part 2") {
+                Some((2, values))
+            } else {
+                None
+            }
+        })
+    }
+
     fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
         s.split(postfix).next()?.parse().ok()
     }
@@ -174,7 +444,7 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -205,9 +475,13 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        #[cfg(unix)]
+        use super::supervise;
+        use super::{check_limits, parse_counters, parse_exec_time, parse_memory};
 
         use crate::day;
+        use crate::template::{run_multi::Limits, timings::Abort};
+        use std::time::Duration;
 
         #[test]
         fn parses_execution_times() {
@@ -253,5 +527,103 @@ pub mod child_commands {
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn parses_memory_stats() {
+            let mut res = parse_exec_time(&[], day!(1));
+            parse_memory(
+                &[
+                    "Part 1: 0 (74.13ns) [peak: 1024 B, total: 4096 B, allocations: 12]".into(),
+                    "Part 2: ✖             ".into(),
+                    "".into(),
+                ],
+                &mut res,
+            );
+            let memory = res.part_1_memory.unwrap();
+            assert_eq!(memory.peak_bytes, 1024);
+            assert_eq!(memory.total_bytes, 4096);
+            assert_eq!(memory.allocations, 12);
+            assert_eq!(res.part_2_memory.is_none(), true);
+        }
+
+        #[test]
+        fn parses_counters() {
+            let mut res = parse_exec_time(&[], day!(1));
+            parse_counters(
+                &[
+                    "Part 1: 0 (74.13ns @ 100 samples) [allocs/iter: 2, bytes/iter: 64 B] [instructions: 400, cycles: 300, cache-misses: 2, branch-misses: 1]".into(),
+                    "Part 2: 10 (74.13ms @ 99 samples)".into(),
+                    "".into(),
+                ],
+                &mut res,
+            );
+            let counters = res.part_1_counters.unwrap();
+            assert_eq!(counters.instructions, 400);
+            assert_eq!(counters.cycles, 300);
+            assert_eq!(counters.cache_misses, 2);
+            assert_eq!(counters.branch_misses, 1);
+            assert_eq!(res.part_2_counters.is_none(), true);
+        }
+
+        #[test]
+        fn checks_limits() {
+            let limits = Limits {
+                day_timeout: Some(Duration::from_secs(10)),
+                part_timeout: Some(Duration::from_secs(2)),
+                memory_limit: Some(1024),
+            };
+            let (second, minute) = (Duration::from_secs(1), Duration::from_secs(60));
+
+            assert_eq!(check_limits(&limits, second, second, || Some(512)), None);
+            assert_eq!(check_limits(&limits, second, second, || None), None);
+            assert_eq!(
+                check_limits(&limits, minute, second, || Some(512)),
+                Some(Abort::Timeout)
+            );
+            assert_eq!(
+                check_limits(&limits, second, 3 * second, || Some(512)),
+                Some(Abort::Timeout)
+            );
+            assert_eq!(
+                check_limits(&limits, second, second, || Some(2048)),
+                Some(Abort::MemoryLimit)
+            );
+        }
+
+        #[test]
+        fn reads_memory_only_with_a_limit() {
+            let limits = Limits {
+                day_timeout: Some(Duration::from_secs(10)),
+                ..Limits::default()
+            };
+            let second = Duration::from_secs(1);
+
+            assert_eq!(
+                check_limits(&limits, second, second, || unreachable!()),
+                None
+            );
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn kills_children_that_time_out() {
+            let child = std::process::Command::new("sh")
+                .args(["-c", "echo 'Part 1: 1'; exec sleep 10"])
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped())
+                .spawn()
+                .unwrap();
+            let limits = Limits {
+                part_timeout: Some(Duration::from_millis(200)),
+                ..Limits::default()
+            };
+
+            let start = std::time::Instant::now();
+            let run = supervise(child, &limits).unwrap();
+
+            assert_eq!(run.aborted, Some(Abort::Timeout));
+            assert_eq!(run.output, vec!["Part 1: 1".to_string()]);
+            assert!(start.elapsed() < Duration::from_secs(5));
+        }
     }
 }
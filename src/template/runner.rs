@@ -6,76 +6,211 @@ use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::memo::{self, MemoStats};
+use crate::template::alloc::AllocCounts;
+use crate::template::config::{Config, OutputFormat};
+use crate::template::panics;
+use crate::template::perf::PerfCounters;
+use crate::template::timings::{CounterStats, MemoryStats};
 use crate::template::ANSI_BOLD;
 use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
 
 pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
-    let part_str = format!("Part {part}");
+    if let Some(result) = run_labelled(func, input, &format!("Part {part}")) {
+        submit_result(result, day, part);
+    }
+}
+
+/// Run a function like a solution part, printing its result and stats under the given label.
+/// Passing `--time` benches it like `cargo time` does. Used for workloads that do not belong to a day, e.g. `examples/`.
+pub fn run_labelled<I: Clone, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    label: &str,
+) -> Option<T> {
+    let (result, stats) = run_timed(func, input, |result| print_result(result, label, ""));
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    print_result(&result, label, &format_stats(&stats));
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    result
+}
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
-    }
+/// Measurements collected while running a solution part.
+struct RunStats {
+    duration: Duration,
+    samples: u128,
+    memory: Option<MemoryStats>,
+    allocs: Option<AllocCounts>,
+    counters: Option<CounterStats>,
+    memo: Vec<(&'static str, MemoStats)>,
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Clone, T>(
-    func: impl Fn(I) -> T,
-    input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
-    let timer = Instant::now();
-    let result = {
+///  2. in release, the function is benched (approx. `bench.target_ms` of execution time or `bench.min_samples` samples,
+///     whatever take longer. See [`Config`].)
+///
+/// The tables of `memoize!` functions are cleared before every execution, so parts and samples don't share cached
+/// values. In debug builds, their hits and misses during the first execution are reported.
+///
+/// When built with the `dhat-heap` feature and passed `--memory`, heap statistics of the first execution are collected
+/// instead of writing a `dhat-heap.json` profile.
+fn run_timed<I: Clone, T>(func: impl Fn(I) -> T, input: I, hook: impl Fn(&T)) -> (T, RunStats) {
+    let ((result, memory, base_time), memo) = with_memo_stats(|| {
+        let timer = Instant::now();
         let input = input.clone();
 
         #[cfg(feature = "dhat-heap")]
-        let _profiler = dhat::Profiler::new_heap();
+        let _profiler = if is_memory_run() {
+            // testing mode disables writing the profile to disk when the profiler is dropped.
+            dhat::Profiler::builder().testing().build()
+        } else {
+            dhat::Profiler::new_heap()
+        };
 
-        func(input)
-    };
-    let base_time = timer.elapsed();
+        let result = func(input);
+
+        #[cfg(feature = "dhat-heap")]
+        let memory = is_memory_run().then(|| {
+            let stats = dhat::HeapStats::get();
+            MemoryStats {
+                peak_bytes: stats.max_bytes as u64,
+                total_bytes: stats.total_bytes,
+                allocations: stats.total_blocks,
+            }
+        });
+
+        #[cfg(not(feature = "dhat-heap"))]
+        let memory = None;
+
+        (result, memory, timer.elapsed())
+    });
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+    let stats = if std::env::args().any(|x| x == "--time") {
+        RunStats {
+            memory,
+            memo,
+            ..bench(func, input, &base_time)
+        }
     } else {
-        (base_time, 1)
+        RunStats {
+            duration: base_time,
+            samples: 1,
+            memory,
+            allocs: None,
+            counters: None,
+            memo,
+        }
     };
 
-    (result, run.0, run.1)
+    (result, stats)
+}
+
+/// Runs `func` with empty `memoize!` tables, returning the hits and misses of the tables it used.
+fn with_memo_stats<T>(func: impl FnOnce() -> T) -> (T, Vec<(&'static str, MemoStats)>) {
+    memo::clear_all();
+    memo::take_stats();
+
+    let result = func();
+
+    memo::clear_all();
+    (result, memo::take_stats())
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+#[cfg(feature = "dhat-heap")]
+fn is_memory_run() -> bool {
+    std::env::args().any(|x| x == "--memory")
+}
+
+/// Bench a solution part. When the `count-allocs` feature is enabled, the average allocations per iteration are
+/// reported alongside the average duration. When passed `--perf`, hardware counters are read around each sample.
+fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> RunStats {
     let mut stdout = stdout();
 
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+    if !is_plain() {
+        print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
+        let _ = stdout.flush();
+    }
 
-    let bench_iterations =
-        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
+    let config = &Config::get().bench;
+    let bench_iterations = (Duration::from_millis(config.target_ms).as_nanos()
+        / cmp::max(base_time.as_nanos(), 10))
+    .clamp(
+        u128::from(config.min_samples),
+        u128::from(config.max_samples),
+    );
 
     let mut timers: Vec<Duration> = vec![];
+    let mut allocs = AllocCounts::default();
+    let mut perf = open_perf_counters();
 
-    for _ in 0..bench_iterations {
-        // need a clone here to make the borrow checker happy.
-        let cloned = input.clone();
-        let timer = Instant::now();
-        black_box(func(black_box(cloned)));
-        timers.push(timer.elapsed());
-    }
+    // samples clear the memo tables too, their stats are only reported for the first execution.
+    memo::without_stats(|| {
+        for _ in 0..bench_iterations {
+            // need a clone here to make the borrow checker happy.
+            let cloned = input.clone();
+            memo::clear_all();
+            let counts = AllocCounts::now();
+
+            let run = || {
+                let timer = Instant::now();
+                black_box(func(black_box(cloned)));
+                timer.elapsed()
+            };
+
+            let elapsed = match perf.as_mut() {
+                None => run(),
+                Some(p) => {
+                    // keep the sample's timing even if its counters could not be read.
+                    let (elapsed, read) = p.sample(run);
+                    if let Err(e) = read {
+                        eprintln!("\nDisabling hardware counters: {e}");
+                        perf = None;
+                    }
+                    elapsed
+                }
+            };
+            timers.push(elapsed);
 
-    (
+            let counts = AllocCounts::now().since(counts);
+            allocs.allocations += counts.allocations;
+            allocs.bytes += counts.bytes;
+        }
+    });
+
+    #[allow(clippy::cast_possible_truncation)]
+    let allocs =
+        cfg!(all(feature = "count-allocs", not(feature = "dhat-heap"))).then(|| AllocCounts {
+            allocations: allocs.allocations / bench_iterations as u64,
+            bytes: allocs.bytes / bench_iterations as u64,
+        });
+
+    RunStats {
         #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+        duration: Duration::from_nanos(average_duration(&timers) as u64),
+        samples: bench_iterations,
+        memory: None,
+        allocs,
+        counters: perf.map(|p| p.average()),
+        memo: Vec::new(),
+    }
+}
+
+/// Opens hardware counters if `--perf` was passed. Falls back to plain timing if they are not available.
+fn open_perf_counters() -> Option<PerfCounters> {
+    if !std::env::args().any(|x| x == "--perf") {
+        return None;
+    }
+
+    match PerfCounters::new() {
+        Ok(perf) => Some(perf),
+        Err(e) => {
+            eprintln!("\nHardware counters are not available: {e}");
+            None
+        }
+    }
 }
 
 fn average_duration(numbers: &[Duration]) -> u128 {
@@ -94,9 +229,81 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
+fn format_memory(memory: &MemoryStats) -> String {
+    format!(
+        " [peak: {} B, total: {} B, allocations: {}]",
+        memory.peak_bytes, memory.total_bytes, memory.allocations
+    )
+}
+
+fn format_allocs(allocs: &AllocCounts) -> String {
+    format!(
+        " [allocs/iter: {}, bytes/iter: {} B]",
+        allocs.allocations, allocs.bytes
+    )
+}
+
+fn format_counters(counters: &CounterStats) -> String {
+    format!(
+        " [instructions: {}, cycles: {}, cache-misses: {}, branch-misses: {}]",
+        counters.instructions, counters.cycles, counters.cache_misses, counters.branch_misses
+    )
+}
+
+fn format_memo(name: &str, stats: &MemoStats) -> String {
+    format!(
+        " [memo {name}: {} hits, {} misses]",
+        stats.hits, stats.misses
+    )
+}
+
+fn format_stats(stats: &RunStats) -> String {
+    let mut str = format_duration(&stats.duration, stats.samples);
+
+    if let Some(allocs) = &stats.allocs {
+        str.push_str(&format_allocs(allocs));
+    }
+
+    if let Some(counters) = &stats.counters {
+        str.push_str(&format_counters(counters));
+    }
+
+    if let Some(memory) = &stats.memory {
+        str.push_str(&format_memory(memory));
+    }
+
+    if cfg!(debug_assertions) {
+        for (name, memo) in &stats.memo {
+            str.push_str(&format_memo(name, memo));
+        }
+    }
+
+    str
+}
+
+fn is_plain() -> bool {
+    Config::get().output.format == OutputFormat::Plain
+}
+
 fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
+    // plain output only prints final results, so there is no need to overwrite the line.
+    if is_plain() {
+        if is_intermediate_result {
+            return;
+        }
+        match result {
+            Some(result) if result.to_string().contains('\n') => {
+                println!("{part}: ▼ {duration_str}");
+                println!("{result}");
+            }
+            Some(result) => println!("{part}: {result}{duration_str}"),
+            None => println!("{part}: ✖"),
+        }
+        return;
+    }
+
     match result {
         Some(result) => {
             if result.to_string().contains('\n') {
@@ -167,3 +374,225 @@ fn submit_result<T: Display>(
     println!("Submitting result via aoc-cli...");
     Some(aoc_cli::submit(day, part, &result.to_string()))
 }
+
+/* -------------------------------------------------------------------------- */
+
+/// The outcome of running a solution part against one of several inputs.
+#[derive(Debug)]
+pub enum Outcome {
+    Answer(String, Duration),
+    NoAnswer(Duration),
+    Panicked(String),
+}
+
+/// Run a solution part once against each of the given named inputs. Panics are caught and reported as outcome.
+///
+/// Unlike [`run_part`], this does not go through `run_timed`: every input runs exactly once, without benching, heap
+/// profiling or submitting, so that inputs which crash the part still end up in the comparison table.
+pub fn compare_part<I: Clone, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    inputs: &[(String, I)],
+) -> Vec<Outcome> {
+    // panics are reported in the comparison table instead of being printed.
+    panics::silenced(|| {
+        inputs
+            .iter()
+            .map(|(_, input)| {
+                let input = input.clone();
+                memo::clear_all();
+                let timer = Instant::now();
+                match panics::catch(|| func(input)) {
+                    Ok(Some(answer)) => Outcome::Answer(answer.to_string(), timer.elapsed()),
+                    Ok(None) => Outcome::NoAnswer(timer.elapsed()),
+                    Err(message) => Outcome::Panicked(message),
+                }
+            })
+            .collect()
+    })
+}
+
+/// Print a table of answers and timings per input, followed by the inputs that crashed or produced no answer.
+pub fn print_comparison<I>(inputs: &[(String, I)], parts: &[(u8, Vec<Outcome>)]) {
+    let names: Vec<&str> = inputs.iter().map(|(name, _)| name.as_str()).collect();
+    println!("{}", format_comparison(&names, parts));
+}
+
+fn format_outcome(outcome: &Outcome) -> String {
+    match outcome {
+        Outcome::Answer(answer, duration) => match answer.split_once('\n') {
+            Some((first_line, _)) => format!("{first_line} … ({duration:.1?})"),
+            None => format!("{answer} ({duration:.1?})"),
+        },
+        Outcome::NoAnswer(duration) => format!("✖ None ({duration:.1?})"),
+        Outcome::Panicked(message) => {
+            format!("✖ panicked: {}", message.lines().next().unwrap_or_default())
+        }
+    }
+}
+
+fn format_comparison(names: &[&str], parts: &[(u8, Vec<Outcome>)]) -> String {
+    let header: Vec<String> = std::iter::once("Input".to_string())
+        .chain(parts.iter().map(|(part, _)| format!("Part {part}")))
+        .collect();
+
+    let rows: Vec<Vec<String>> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            std::iter::once((*name).to_string())
+                .chain(
+                    parts
+                        .iter()
+                        .map(|(_, outcomes)| format_outcome(&outcomes[i])),
+                )
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|col| {
+            std::iter::once(&header)
+                .chain(&rows)
+                .map(|row| row[col].chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let format_row = |row: &[String]| {
+        row.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![format_row(&header)];
+    lines.push(
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-"),
+    );
+    lines.extend(rows.iter().map(|row| format_row(row)));
+
+    let failed: Vec<&str> = names
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| {
+            parts
+                .iter()
+                .any(|(_, outcomes)| !matches!(outcomes[*i], Outcome::Answer(..)))
+        })
+        .map(|(_, name)| *name)
+        .collect();
+
+    lines.push(String::new());
+    if failed.is_empty() {
+        lines.push(format!("All {} inputs produced answers.", names.len()));
+    } else {
+        lines.push(format!(
+            "✖ {} of {} inputs crashed or produced no answer: {}",
+            failed.len(),
+            names.len(),
+            failed.join(", ")
+        ));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{compare_part, format_comparison, with_memo_stats, Outcome};
+    use crate::memo::MemoStats;
+    use std::time::Duration;
+
+    crate::memoize! {
+        fn triangle(n: u64) -> u64 {
+            if n == 0 {
+                0
+            } else {
+                n + triangle(n - 1)
+            }
+        }
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn parse_sum(input: &str) -> Option<u32> {
+        let sum = input.lines().map(|l| l.parse::<u32>().unwrap()).sum();
+        (sum > 0).then_some(sum)
+    }
+
+    #[test]
+    fn compares_inputs() {
+        let inputs = vec![
+            ("a.txt".to_string(), "1\n2"),
+            ("b.txt".to_string(), "0"),
+            ("c.txt".to_string(), "x"),
+        ];
+
+        let outcomes = compare_part(parse_sum, &inputs);
+
+        assert!(matches!(&outcomes[0], Outcome::Answer(answer, _) if answer == "3"));
+        assert!(matches!(outcomes[1], Outcome::NoAnswer(_)));
+        assert!(
+            matches!(&outcomes[2], Outcome::Panicked(message) if message.contains("ParseIntError"))
+        );
+    }
+
+    #[test]
+    fn formats_comparison() {
+        let parts = vec![
+            (
+                1,
+                vec![
+                    Outcome::Answer("42".into(), Duration::from_millis(1)),
+                    Outcome::NoAnswer(Duration::from_millis(2)),
+                ],
+            ),
+            (
+                2,
+                vec![
+                    Outcome::Answer("line 1\nline 2".into(), Duration::from_millis(3)),
+                    Outcome::Panicked("oops\nbacktrace".into()),
+                ],
+            ),
+        ];
+
+        assert_eq!(
+            format_comparison(&["alice.txt", "bob.txt"], &parts),
+            [
+                "Input     | Part 1         | Part 2",
+                "----------+----------------+-----------------",
+                "alice.txt | 42 (1.0ms)     | line 1 … (3.0ms)",
+                "bob.txt   | ✖ None (2.0ms) | ✖ panicked: oops",
+                "",
+                "✖ 1 of 2 inputs crashed or produced no answer: bob.txt",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn clears_memo_tables_between_runs() {
+        let (first, first_memo) = with_memo_stats(|| triangle(10));
+        let (second, second_memo) = with_memo_stats(|| triangle(10));
+
+        assert_eq!((first, second), (55, 55));
+        assert_eq!(
+            first_memo,
+            vec![(
+                "triangle",
+                MemoStats {
+                    hits: 0,
+                    misses: 11
+                }
+            )]
+        );
+        assert_eq!(second_memo, first_memo);
+    }
+}
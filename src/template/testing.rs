@@ -0,0 +1,507 @@
+/// Helpers for property-based and differential testing of solutions.
+///
+/// Inputs are produced by a user-supplied generator from a seeded [`Rng`]. When a check fails, the input is shrunk to a
+/// minimal failing case, which is written to the examples directory for reproduction.
+///
+/// ```
+/// # use advent_of_code::{day, template::testing::Check};
+/// # fn brute_force(input: &str) -> Option<u32> { None }
+/// # fn optimized(input: &str) -> Option<u32> { None }
+/// Check::new(day!(1)).cases(200).differential(
+///     |rng| (0..rng.range(1..=20)).map(|_| format!("{}   {}\n", rng.range(0..=99), rng.range(0..=99))).collect(),
+///     brute_force,
+///     optimized,
+/// );
+/// ```
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs};
+
+use crate::template::config::Config;
+use crate::template::panics::{catch, silenced};
+use crate::template::Day;
+
+/// Upper bound for the number of candidates tried while shrinking a failing input.
+const MAX_SHRINK_ATTEMPTS: usize = 2000;
+
+/// A small, seedable pseudo-random number generator (SplitMix64).
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the given inclusive range.
+    ///
+    /// # Panics
+    /// If the range is empty.
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "cannot sample from an empty range");
+        #[allow(clippy::cast_sign_loss)]
+        let span = end.wrapping_sub(start) as u64;
+        let offset = match span.checked_add(1) {
+            Some(n) => self.next_u64() % n,
+            None => self.next_u64(),
+        };
+        #[allow(clippy::cast_possible_wrap)]
+        start.wrapping_add(offset as i64)
+    }
+
+    /// Returns an index below `n`.
+    ///
+    /// # Panics
+    /// If `n` is zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "cannot sample below zero");
+        #[allow(clippy::cast_possible_truncation)]
+        let index = (self.next_u64() % n as u64) as usize;
+        index
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    /// Returns `true` with the given probability.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64) < probability
+    }
+
+    /// Returns a random element of `items`.
+    ///
+    /// # Panics
+    /// If `items` is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Runs generated inputs against solution parts.
+#[derive(Debug, Clone)]
+pub struct Check {
+    day: Day,
+    cases: usize,
+    seed: u64,
+    write_failures: bool,
+}
+
+/// Why a generated input failed a check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Failure {
+    /// The property returned an error.
+    Violated(String),
+    /// The implementations returned different results.
+    Mismatch(String),
+    /// The given implementation panicked.
+    Panicked(&'static str, String),
+}
+
+impl Failure {
+    /// Whether two failures are of the same kind. Shrinking must not turn one kind of failure into another.
+    fn same_kind(&self, other: &Failure) -> bool {
+        match (self, other) {
+            (Failure::Panicked(a, _), Failure::Panicked(b, _)) => a == b,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Failure::Violated(message) => format!("property violated: {message}"),
+            Failure::Mismatch(message) => format!("implementations disagree: {message}"),
+            Failure::Panicked(name, message) => format!("{name} panicked: {message}"),
+        }
+    }
+}
+
+impl Check {
+    /// Creates a check with 100 cases. The seed is read from `AOC_TEST_SEED`, or picked at random.
+    pub fn new(day: Day) -> Self {
+        let seed = env::var("AOC_TEST_SEED")
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or_else(|| {
+                let nanos = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_nanos());
+                #[allow(clippy::cast_possible_truncation)]
+                let seed = nanos as u64;
+                seed
+            });
+
+        Self {
+            day,
+            cases: 100,
+            seed,
+            write_failures: true,
+        }
+    }
+
+    /// Sets the number of generated inputs.
+    #[must_use]
+    pub fn cases(mut self, cases: usize) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    /// Sets the seed, e.g. to reproduce a failure.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Whether minimal failing inputs are written to the examples directory. Enabled by default.
+    #[must_use]
+    pub fn write_failures(mut self, write_failures: bool) -> Self {
+        self.write_failures = write_failures;
+        self
+    }
+
+    /// Checks that `property` holds for every generated input.
+    ///
+    /// # Panics
+    /// If the property returns an error or panics for any input.
+    pub fn property(
+        &self,
+        generator: impl Fn(&mut Rng) -> String,
+        property: impl Fn(&str) -> Result<(), String>,
+    ) {
+        self.run(generator, |input| match catch(|| property(input)) {
+            Ok(Ok(())) => None,
+            Ok(Err(message)) => Some(Failure::Violated(message)),
+            Err(message) => Some(Failure::Panicked("property", message)),
+        });
+    }
+
+    /// Checks that `reference` and `candidate` return the same result for every generated input.
+    /// If both implementations panic on an input, it is considered invalid and skipped.
+    ///
+    /// # Panics
+    /// If the results differ, or only one of the implementations panics for any input.
+    pub fn differential<T: PartialEq + Debug>(
+        &self,
+        generator: impl Fn(&mut Rng) -> String,
+        reference: impl Fn(&str) -> T,
+        candidate: impl Fn(&str) -> T,
+    ) {
+        self.run(generator, |input| {
+            match (catch(|| reference(input)), catch(|| candidate(input))) {
+                (Ok(a), Ok(b)) if a == b => None,
+                (Ok(a), Ok(b)) => Some(Failure::Mismatch(format!(
+                    "reference returned {a:?}, candidate returned {b:?}"
+                ))),
+                (Err(message), Ok(_)) => Some(Failure::Panicked("reference", message)),
+                (Ok(_), Err(message)) => Some(Failure::Panicked("candidate", message)),
+                (Err(_), Err(_)) => None,
+            }
+        });
+    }
+
+    fn run(&self, generator: impl Fn(&mut Rng) -> String, test: impl Fn(&str) -> Option<Failure>) {
+        let mut rng = Rng::new(self.seed);
+
+        for case in 0..self.cases {
+            let input = generator(&mut rng);
+
+            let Some(failure) = silenced(|| test(&input)) else {
+                continue;
+            };
+
+            let (input, failure) = silenced(|| shrink(input, failure, |candidate| test(candidate)));
+
+            let saved = if self.write_failures {
+                match self.write_failure(&input) {
+                    Ok(path) => format!(
+                        "\nSaved to \"{}\". Reproduce with `cargo solve {} --input {}`.",
+                        path.display(),
+                        self.day,
+                        path.display()
+                    ),
+                    Err(e) => format!("\nCould not save failing input: {e}"),
+                }
+            } else {
+                String::new()
+            };
+
+            panic!(
+                "Check failed for day {} on case {case} (seed {}): {}\nMinimal input:\n{input}{saved}",
+                self.day,
+                self.seed,
+                failure.message()
+            );
+        }
+    }
+
+    fn write_failure(&self, input: &str) -> std::io::Result<PathBuf> {
+        let mut hasher = DefaultHasher::new();
+        input.hash(&mut hasher);
+
+        let dir = &Config::get().paths.examples;
+        #[allow(clippy::cast_possible_truncation)]
+        let hash = hasher.finish() as u32;
+        let path = dir.join(format!("{}-fail-{hash:08x}.txt", self.day));
+        fs::create_dir_all(dir)?;
+        fs::write(&path, input)?;
+        Ok(path)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Shrinks a failing input while it keeps failing in the same way. Tries, in order:
+///  1. removing chunks of lines, halving the chunk size down to single lines.
+///  2. replacing integers with smaller ones, towards zero.
+fn shrink(
+    input: String,
+    failure: Failure,
+    test: impl Fn(&str) -> Option<Failure>,
+) -> (String, Failure) {
+    let mut best = (input, failure);
+    let attempts = Cell::new(0);
+
+    let try_candidate = |best: &mut (String, Failure), candidate: String| -> bool {
+        attempts.set(attempts.get() + 1);
+        if attempts.get() > MAX_SHRINK_ATTEMPTS
+            || candidate.len() >= best.0.len() && candidate >= best.0
+        {
+            return false;
+        }
+        match test(&candidate) {
+            Some(failure) if failure.same_kind(&best.1) => {
+                *best = (candidate, failure);
+                true
+            }
+            _ => false,
+        }
+    };
+
+    loop {
+        let mut progress = false;
+
+        let trailing_newline = if best.0.ends_with('\n') { "\n" } else { "" };
+        let mut chunk = best.0.lines().count() / 2;
+
+        while chunk > 0 {
+            let lines: Vec<String> = best.0.lines().map(String::from).collect();
+            let mut start = 0;
+            let mut removed_any = false;
+
+            while start < lines.len() {
+                let mut kept = lines.clone();
+                let end = (start + chunk).min(kept.len());
+                kept.drain(start..end);
+
+                let mut candidate = kept.join("\n");
+                if !candidate.is_empty() {
+                    candidate.push_str(trailing_newline);
+                }
+
+                if try_candidate(&mut best, candidate) {
+                    removed_any = true;
+                    progress = true;
+                    break;
+                }
+                start += chunk;
+            }
+
+            if !removed_any {
+                chunk /= 2;
+            }
+        }
+
+        for (range, value) in integers(&best.0) {
+            // try values ever closer to the original one: 0, v/2, 3v/4, ..., v-1.
+            let mut delta = value;
+            let mut smaller = vec![];
+            while delta != 0 {
+                smaller.push(value - delta);
+                delta /= 2;
+            }
+
+            for replacement in smaller {
+                let mut candidate = best.0.clone();
+                candidate.replace_range(range.clone(), &replacement.to_string());
+                if try_candidate(&mut best, candidate) {
+                    progress = true;
+                    break;
+                }
+            }
+            if progress {
+                break;
+            }
+        }
+
+        if !progress || attempts.get() > MAX_SHRINK_ATTEMPTS {
+            return best;
+        }
+    }
+}
+
+/// Finds the byte ranges and values of all integers in `s`.
+fn integers(s: &str) -> Vec<(std::ops::Range<usize>, i64)> {
+    let bytes = s.as_bytes();
+    let mut result = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let is_negative = bytes[i] == b'-'
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+            && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric());
+
+        if bytes[i].is_ascii_digit() || is_negative {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            if let Ok(value) = s[start..i].parse() {
+                result.push((start..i, value));
+            }
+        } else {
+            i += 1;
+        }
+    }
+
+    result
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{integers, shrink, Check, Failure, Rng};
+    use crate::day;
+    use std::panic;
+
+    #[test]
+    fn rng_is_deterministic() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn rng_respects_ranges() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            assert!((-3..=3).contains(&rng.range(-3..=3)));
+            assert!(rng.below(5) < 5);
+        }
+        assert_eq!(rng.range(4..=4), 4);
+        rng.range(i64::MIN..=i64::MAX);
+    }
+
+    #[test]
+    fn finds_integers() {
+        let found: Vec<i64> = integers("a-1 -23 x4,b-5\n600")
+            .into_iter()
+            .map(|(_, v)| v)
+            .collect();
+        assert_eq!(found, vec![1, -23, 4, 5, 600]);
+    }
+
+    #[test]
+    fn shrinks_to_minimal_input() {
+        // fails if any line contains a number greater than 10.
+        let test = |input: &str| {
+            input
+                .lines()
+                .any(|l| l.parse::<i64>().unwrap_or(0) > 10)
+                .then(|| Failure::Violated("too large".into()))
+        };
+
+        let input = "1\n2\n345\n4\n99\n6\n".to_string();
+        let failure = test(&input).unwrap();
+        let (shrunk, _) = shrink(input, failure, test);
+
+        assert_eq!(shrunk, "11\n");
+    }
+
+    #[test]
+    fn passes_matching_implementations() {
+        Check::new(day!(1)).seed(3).cases(50).differential(
+            |rng| format!("{}", rng.range(0..=1000)),
+            |input| input.parse::<u32>().unwrap() * 2,
+            |input| input.parse::<u32>().unwrap() << 1,
+        );
+    }
+
+    #[test]
+    fn detects_and_shrinks_mismatches() {
+        let result = panic::catch_unwind(|| {
+            Check::new(day!(1))
+                .seed(3)
+                .write_failures(false)
+                .differential(
+                    |rng| {
+                        (0..rng.range(1..=10))
+                            .map(|_| format!("{}\n", rng.range(0..=1000)))
+                            .collect()
+                    },
+                    |input| {
+                        input
+                            .lines()
+                            .map(|l| l.parse::<u32>().unwrap())
+                            .sum::<u32>()
+                    },
+                    |input| {
+                        input
+                            .lines()
+                            .map(|l| l.parse::<u32>().unwrap().min(500))
+                            .sum::<u32>()
+                    },
+                );
+        });
+
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("implementations disagree"));
+        assert!(message.ends_with("Minimal input:\n501\n"), "{message}");
+    }
+
+    #[test]
+    fn detects_property_violations() {
+        let result = panic::catch_unwind(|| {
+            Check::new(day!(1)).seed(5).write_failures(false).property(
+                |rng| format!("{}", rng.range(0..=100)),
+                |input| {
+                    let n: u32 = input.parse().unwrap();
+                    (n < 50).then_some(()).ok_or(format!("{n} is too large"))
+                },
+            );
+        });
+
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(
+            message.contains("property violated: 50 is too large"),
+            "{message}"
+        );
+    }
+}
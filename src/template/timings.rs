@@ -1,9 +1,62 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{collections::HashMap, fmt::Display, fs, io::Error, str::FromStr};
 use tinyjson::JsonValue;
 
+use crate::template::config::Config;
 use crate::template::Day;
 
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+/// Represents heap allocation statistics for a single solution part, as collected by dhat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryStats {
+    pub peak_bytes: u64,
+    pub total_bytes: u64,
+    pub allocations: u64,
+}
+
+/// Represents hardware counter values for a single solution part, averaged per benchmark sample.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CounterStats {
+    pub instructions: u64,
+    pub cycles: u64,
+    pub cache_misses: u64,
+    pub branch_misses: u64,
+}
+
+/// The reason a solution run was aborted before completing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Abort {
+    Timeout,
+    MemoryLimit,
+}
+
+impl Abort {
+    fn as_str(self) -> &'static str {
+        match self {
+            Abort::Timeout => "timeout",
+            Abort::MemoryLimit => "memory_limit",
+        }
+    }
+}
+
+impl Display for Abort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Abort::Timeout => write!(f, "timed out"),
+            Abort::MemoryLimit => write!(f, "exceeded memory limit"),
+        }
+    }
+}
+
+impl FromStr for Abort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "timeout" => Ok(Abort::Timeout),
+            "memory_limit" => Ok(Abort::MemoryLimit),
+            _ => Err(format!("Unknown abort reason `{s}`.")),
+        }
+    }
+}
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
@@ -11,6 +64,11 @@ pub struct Timing {
     pub day: Day,
     pub part_1: Option<String>,
     pub part_2: Option<String>,
+    pub part_1_memory: Option<MemoryStats>,
+    pub part_2_memory: Option<MemoryStats>,
+    pub part_1_counters: Option<CounterStats>,
+    pub part_2_counters: Option<CounterStats>,
+    pub aborted: Option<Abort>,
     pub total_nanos: f64,
 }
 
@@ -25,13 +83,13 @@ impl Timings {
     /// Dehydrate timings to a JSON file.
     pub fn store_file(&self) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        let mut file = fs::File::create(&Config::get().paths.timings)?;
         json.format_to(&mut file)
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
     pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
+        fs::read_to_string(&Config::get().paths.timings)
             .map_err(|x| x.to_string())
             .and_then(Timings::try_from)
             .unwrap_or_default()
@@ -51,7 +109,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.day);
         Timings { data }
     }
 
@@ -65,6 +123,13 @@ impl Timings {
             .iter()
             .any(|t| t.day == day && t.part_1.is_some() && t.part_2.is_some())
     }
+
+    /// Whether any of the timings carries memory statistics.
+    pub fn has_memory(&self) -> bool {
+        self.data
+            .iter()
+            .any(|t| t.part_1_memory.is_some() || t.part_2_memory.is_some())
+    }
 }
 
 /* -------------------------------------------------------------------------- */
@@ -133,6 +198,37 @@ impl From<&Timing> for JsonValue {
             },
         );
 
+        map.insert(
+            "part_1_memory".into(),
+            value.part_1_memory.map_or(JsonValue::Null, JsonValue::from),
+        );
+
+        map.insert(
+            "part_2_memory".into(),
+            value.part_2_memory.map_or(JsonValue::Null, JsonValue::from),
+        );
+
+        map.insert(
+            "part_1_counters".into(),
+            value
+                .part_1_counters
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
+
+        map.insert(
+            "part_2_counters".into(),
+            value
+                .part_2_counters
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
+
+        map.insert(
+            "aborted".into(),
+            value
+                .aborted
+                .map_or(JsonValue::Null, |x| JsonValue::String(x.as_str().into())),
+        );
+
         JsonValue::Object(map)
     }
 }
@@ -166,10 +262,45 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        // NOTE: memory stats and counters are optional to keep reading timings stored by older versions.
+        let part_1_memory = match json.get("part_1_memory") {
+            Some(v) if !v.is_null() => Some(MemoryStats::try_from(v)?),
+            _ => None,
+        };
+
+        let part_2_memory = match json.get("part_2_memory") {
+            Some(v) if !v.is_null() => Some(MemoryStats::try_from(v)?),
+            _ => None,
+        };
+
+        let part_1_counters = match json.get("part_1_counters") {
+            Some(v) if !v.is_null() => Some(CounterStats::try_from(v)?),
+            _ => None,
+        };
+
+        let part_2_counters = match json.get("part_2_counters") {
+            Some(v) if !v.is_null() => Some(CounterStats::try_from(v)?),
+            _ => None,
+        };
+
+        let aborted = match json.get("aborted") {
+            Some(v) if !v.is_null() => Some(
+                v.get::<String>()
+                    .ok_or("Expected timing.aborted to be null or string.")?
+                    .parse()?,
+            ),
+            _ => None,
+        };
+
         Ok(Timing {
             day,
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
+            part_1_memory,
+            part_2_memory,
+            part_1_counters,
+            part_2_counters,
+            aborted,
             total_nanos,
         })
     }
@@ -177,6 +308,102 @@ impl TryFrom<&JsonValue> for Timing {
 
 /* -------------------------------------------------------------------------- */
 
+impl From<MemoryStats> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: MemoryStats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "peak_bytes".into(),
+            JsonValue::Number(value.peak_bytes as f64),
+        );
+        map.insert(
+            "total_bytes".into(),
+            JsonValue::Number(value.total_bytes as f64),
+        );
+        map.insert(
+            "allocations".into(),
+            JsonValue::Number(value.allocations as f64),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for MemoryStats {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected memory stats to be a JSON object.")?;
+
+        let get_number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>())
+                .map(|x| *x as u64)
+                .ok_or(format!("Expected memory.{key} to be a number."))
+        };
+
+        Ok(MemoryStats {
+            peak_bytes: get_number("peak_bytes")?,
+            total_bytes: get_number("total_bytes")?,
+            allocations: get_number("allocations")?,
+        })
+    }
+}
+
+impl From<CounterStats> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: CounterStats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "instructions".into(),
+            JsonValue::Number(value.instructions as f64),
+        );
+        map.insert("cycles".into(), JsonValue::Number(value.cycles as f64));
+        map.insert(
+            "cache_misses".into(),
+            JsonValue::Number(value.cache_misses as f64),
+        );
+        map.insert(
+            "branch_misses".into(),
+            JsonValue::Number(value.branch_misses as f64),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for CounterStats {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected counters to be a JSON object.")?;
+
+        let get_number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>())
+                .map(|x| *x as u64)
+                .ok_or(format!("Expected counters.{key} to be a number."))
+        };
+
+        Ok(CounterStats {
+            instructions: get_number("instructions")?,
+            cycles: get_number("cycles")?,
+            cache_misses: get_number("cache_misses")?,
+            branch_misses: get_number("branch_misses")?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
 #[cfg(feature = "test_lib")]
 mod tests {
     use crate::day;
@@ -190,18 +417,33 @@ mod tests {
                     day: day!(1),
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
+                    aborted: None,
                     total_nanos: 3e+10,
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
+                    aborted: None,
                     total_nanos: 7e+10,
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
+                    aborted: None,
                     total_nanos: 4e+10,
                 },
             ],
@@ -209,7 +451,10 @@ mod tests {
     }
 
     mod deserialization {
-        use crate::{day, template::timings::Timings};
+        use crate::{
+            day,
+            template::timings::{Abort, CounterStats, MemoryStats, Timings},
+        };
 
         #[test]
         fn handles_json_timings() {
@@ -221,6 +466,49 @@ mod tests {
             assert_eq!(timing.part_1, Some("1ms".to_string()));
             assert_eq!(timing.part_2, None);
             assert_eq!(timing.total_nanos, 1_000_000_000_f64);
+            assert_eq!(timing.part_1_memory, None);
+        }
+
+        #[test]
+        fn handles_json_memory_stats() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "part_1_memory": { "peak_bytes": 1024, "total_bytes": 4096, "allocations": 12 }, "part_2_memory": null, "total_nanos": 1000000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(
+                timing.part_1_memory,
+                Some(MemoryStats {
+                    peak_bytes: 1024,
+                    total_bytes: 4096,
+                    allocations: 12
+                })
+            );
+            assert_eq!(timing.part_2_memory, None);
+        }
+
+        #[test]
+        fn handles_json_counters() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": "2ms", "part_2_counters": { "instructions": 4000, "cycles": 3000, "cache_misses": 20, "branch_misses": 10 }, "total_nanos": 3000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.part_1_counters, None);
+            assert_eq!(timing.aborted, None);
+            assert_eq!(
+                timing.part_2_counters,
+                Some(CounterStats {
+                    instructions: 4000,
+                    cycles: 3000,
+                    cache_misses: 20,
+                    branch_misses: 10
+                })
+            );
+        }
+
+        #[test]
+        fn handles_aborted_timings() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "aborted": "timeout", "total_nanos": 1000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.aborted, Some(Abort::Timeout));
         }
 
         #[test]
@@ -281,11 +569,16 @@ mod tests {
                     day: day!(1),
                     part_1: Some("1ms".into()),
                     part_2: Some("2ms".into()),
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
+                    aborted: None,
                     total_nanos: 3_000_000_000_f64,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -295,11 +588,16 @@ mod tests {
                     day: day!(1),
                     part_1: Some("1ms".into()),
                     part_2: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
+                    aborted: None,
                     total_nanos: 1_000_000_000_f64,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -309,11 +607,16 @@ mod tests {
                     day: day!(1),
                     part_1: None,
                     part_2: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
+                    aborted: None,
                     total_nanos: 0.0,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
@@ -333,6 +636,11 @@ mod tests {
                     day: day!(3),
                     part_1: None,
                     part_2: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
+                    aborted: None,
                     total_nanos: 0_f64,
                 }],
             };
@@ -353,6 +661,11 @@ mod tests {
                     day: day!(2),
                     part_1: None,
                     part_2: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
+                    aborted: None,
                     total_nanos: 0_f64,
                 }],
             };
@@ -0,0 +1,294 @@
+/// Puzzle unlock times, and waiting for them with an injectable clock.
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, FixedOffset, TimeZone, Utc};
+
+use crate::template::day::SERVER_UTC_OFFSET;
+use crate::template::fetch::{Fetch, FetchError};
+use crate::template::Day;
+
+/// A source of the current time that can also wait.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+    fn sleep(&self, duration: Duration);
+}
+
+/// The system clock.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// The moment a puzzle unlocks: midnight in the server's timezone (UTC-5).
+pub fn unlock_time(year: u16, day: Day) -> DateTime<Utc> {
+    FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)
+        .and_then(|offset| {
+            offset
+                .with_ymd_and_hms(year.into(), 12, day.into_inner().into(), 0, 0, 0)
+                .single()
+        })
+        .expect("every day of advent is a valid date")
+        .with_timezone(&Utc)
+}
+
+/// The next puzzle that unlocks strictly after `now`, along with its year and unlock time.
+/// Returns `None` once the last puzzle of the current event has unlocked.
+pub fn next_unlock(now: DateTime<Utc>) -> Option<(u16, Day, DateTime<Utc>)> {
+    let year = u16::try_from(now.year()).ok()?;
+    (1..=25)
+        .filter_map(Day::new)
+        .map(|day| (year, day, unlock_time(year, day)))
+        .find(|(_, _, time)| *time > now)
+}
+
+/// Formats a countdown as `[Nd ]HH:MM:SS`, rounding up to the next second.
+pub fn format_countdown(remaining: Duration) -> String {
+    let total = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let (days, hours) = (total / 86400, total % 86400 / 3600);
+    let (minutes, seconds) = (total % 3600 / 60, total % 60);
+    if days > 0 {
+        format!("{days}d {hours:02}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{hours:02}:{minutes:02}:{seconds:02}")
+    }
+}
+
+/// Sleeps until `target`, calling `tick` with the remaining time about once per second.
+pub fn wait_until(clock: &impl Clock, target: DateTime<Utc>, mut tick: impl FnMut(Duration)) {
+    while let Ok(remaining) = (target - clock.now()).to_std() {
+        if remaining.is_zero() {
+            break;
+        }
+        tick(remaining);
+        clock.sleep(remaining.min(Duration::from_secs(1)));
+    }
+}
+
+/// Delays between attempts, doubling from `initial` up to `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    /// The number of attempts, including the first one.
+    pub attempts: u32,
+}
+
+impl Default for Backoff {
+    /// Retries for about ten minutes, which covers the initial rush on the servers.
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+            attempts: 16,
+        }
+    }
+}
+
+impl Backoff {
+    /// The delay before the given retry, counting from `0`.
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.initial
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max)
+    }
+}
+
+/// Fetches a day, retrying failures that may be temporary. `on_error` is called with each failure and the delay before the next attempt.
+pub fn fetch_with_retry(
+    clock: &impl Clock,
+    fetcher: &mut impl Fetch,
+    day: Day,
+    backoff: Backoff,
+    mut on_error: impl FnMut(&FetchError, Duration),
+) -> Result<(), FetchError> {
+    let mut retry = 0;
+    loop {
+        match fetcher.fetch(day) {
+            Ok(()) => return Ok(()),
+            Err(e) if e.is_retryable() && retry + 1 < backoff.attempts => {
+                let delay = backoff.delay(retry);
+                on_error(&e, delay);
+                clock.sleep(delay);
+                retry += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::template::config::Config;
+    use crate::template::fetch::tests::{mock_server, not_found, ok};
+    use crate::template::fetch::HttpFetcher;
+    use std::cell::{Cell, RefCell};
+    use std::{env, fs, process};
+
+    /// A clock that only moves when slept on.
+    struct FakeClock {
+        now: Cell<DateTime<Utc>>,
+        sleeps: RefCell<Vec<Duration>>,
+    }
+
+    impl FakeClock {
+        fn at(now: &str) -> Self {
+            Self {
+                now: Cell::new(now.parse().unwrap()),
+                sleeps: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.now.get()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.sleeps.borrow_mut().push(duration);
+            self.now
+                .set(self.now.get() + chrono::Duration::from_std(duration).unwrap());
+        }
+    }
+
+    #[test]
+    fn finds_next_unlock() {
+        let day = |d| Day::new(d).unwrap();
+        assert_eq!(
+            unlock_time(2024, day(1)),
+            "2024-12-01T05:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+
+        let now = "2026-10-19T12:00:00Z".parse().unwrap();
+        assert_eq!(
+            next_unlock(now),
+            Some((2026, day(1), unlock_time(2026, day(1))))
+        );
+
+        // the evening before in new york is already the next day in utc.
+        let now = "2026-12-02T04:59:59Z".parse().unwrap();
+        assert_eq!(next_unlock(now).map(|(_, d, _)| d), Some(day(2)));
+        let now = "2026-12-02T05:00:00Z".parse().unwrap();
+        assert_eq!(next_unlock(now).map(|(_, d, _)| d), Some(day(3)));
+
+        let now = "2026-12-25T05:00:00Z".parse().unwrap();
+        assert_eq!(next_unlock(now), None);
+    }
+
+    #[test]
+    fn formats_countdown() {
+        assert_eq!(format_countdown(Duration::from_millis(1500)), "00:00:02");
+        assert_eq!(format_countdown(Duration::from_secs(3723)), "01:02:03");
+        assert_eq!(
+            format_countdown(Duration::from_secs(86400 + 7384)),
+            "1d 02:03:04"
+        );
+    }
+
+    #[test]
+    fn waits_until_unlock() {
+        let clock = FakeClock::at("2026-12-01T04:59:57.500Z");
+        let target = unlock_time(2026, Day::new(1).unwrap());
+        let mut ticks = Vec::new();
+        wait_until(&clock, target, |remaining| {
+            ticks.push(format_countdown(remaining))
+        });
+
+        assert_eq!(clock.now(), target);
+        assert_eq!(ticks, vec!["00:00:03", "00:00:02", "00:00:01"]);
+        assert_eq!(
+            clock.sleeps.borrow().last(),
+            Some(&Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn backs_off() {
+        let backoff = Backoff {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(5),
+            attempts: 3,
+        };
+        let delays: Vec<u64> = (0..5).map(|i| backoff.delay(i).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 5, 5]);
+        assert_eq!(Backoff::default().delay(u32::MAX), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn fetches_from_mock_server_after_unlock() {
+        let dir = env::temp_dir().join(format!("aoc-unlock-{}", process::id()));
+        let (url, server) = mock_server(vec![
+            not_found(),
+            not_found(),
+            ok("1\n2\n3\n"),
+            ok("<html><article><h2>--- Day 1 ---</h2></article></html>"),
+        ]);
+
+        let mut config = Config::default();
+        config.paths.inputs = dir.join("inputs");
+        config.paths.puzzles = dir.join("puzzles");
+        let mut fetcher = HttpFetcher::new(&url, 2026, &config);
+        fetcher.session = Some("cookie".into());
+
+        let day = Day::new(1).unwrap();
+        let clock = FakeClock::at("2026-12-01T04:59:58Z");
+        wait_until(&clock, unlock_time(2026, day), |_| {});
+
+        let mut errors = Vec::new();
+        fetch_with_retry(&clock, &mut fetcher, day, Backoff::default(), |e, delay| {
+            errors.push((e.to_string(), delay))
+        })
+        .unwrap();
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 4);
+        assert!(requests[0].starts_with("GET /2026/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=cookie\r\n"));
+        assert!(requests[3].starts_with("GET /2026/day/1 HTTP/1.1\r\n"));
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[1].1, Duration::from_secs(2));
+        assert_eq!(
+            clock.now(),
+            "2026-12-01T05:00:03Z".parse::<DateTime<Utc>>().unwrap()
+        );
+
+        assert_eq!(
+            fs::read_to_string(dir.join("inputs/01.txt")).unwrap(),
+            "1\n2\n3\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("puzzles/01.html")).unwrap(),
+            "<article><h2>--- Day 1 ---</h2></article>"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn gives_up_on_permanent_errors() {
+        let (url, server) = mock_server(vec![
+            "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n".into(),
+        ]);
+        let mut fetcher = HttpFetcher::new(&url, 2026, &Config::default());
+        let clock = FakeClock::at("2026-12-01T05:00:00Z");
+
+        let result = fetch_with_retry(
+            &clock,
+            &mut fetcher,
+            Day::new(1).unwrap(),
+            Backoff::default(),
+            |_, _| panic!("should not retry"),
+        );
+        assert!(matches!(result, Err(FetchError::Status(400))));
+        assert_eq!(server.join().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::VecDeque;
+
+/** an occurrence of a pattern, as byte offsets into the searched text */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Match {
+    /** the index of the pattern in the list the automaton was built from */
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Node {
+    // sorted by byte
+    children: Vec<(u8, usize)>,
+    fail: usize,
+    // patterns ending here, including those that end in a suffix of this node, longest first
+    outputs: Vec<usize>,
+}
+
+impl Node {
+    fn child(&self, byte: u8) -> Option<usize> {
+        self.children.binary_search_by_key(&byte, |(b, _)| *b).ok().map(|i| self.children[i].1)
+    }
+}
+
+/** finds every occurrence of several patterns in one pass over the text */
+#[derive(Clone, Debug)]
+pub struct AhoCorasick {
+    nodes: Vec<Node>,
+    lengths: Vec<usize>,
+}
+
+impl AhoCorasick {
+    /** panics if a pattern is empty */
+    pub fn new<P: AsRef<str>>(patterns: impl IntoIterator<Item = P>) -> Self {
+        let mut nodes = vec![Node::default()];
+        let mut lengths = Vec::new();
+        for (i, pattern) in patterns.into_iter().enumerate() {
+            let pattern = pattern.as_ref().as_bytes();
+            assert!(!pattern.is_empty(), "patterns must not be empty");
+            let mut node = 0;
+            for &byte in pattern {
+                node = match nodes[node].child(byte) {
+                    Some(child) => child,
+                    None => {
+                        nodes.push(Node::default());
+                        let child = nodes.len() - 1;
+                        let children = &mut nodes[node].children;
+                        let at = children.partition_point(|(b, _)| *b < byte);
+                        children.insert(at, (byte, child));
+                        child
+                    }
+                };
+            }
+            nodes[node].outputs.push(i);
+            lengths.push(pattern.len());
+        }
+
+        // breadth first, so the fail link of every parent is known before its children
+        let mut queue: VecDeque<usize> = nodes[0].children.iter().map(|(_, child)| *child).collect();
+        while let Some(node) = queue.pop_front() {
+            for (byte, child) in nodes[node].children.clone() {
+                let mut fail = nodes[node].fail;
+                let target = loop {
+                    if let Some(next) = nodes[fail].child(byte) {
+                        break next;
+                    }
+                    if fail == 0 {
+                        break 0;
+                    }
+                    fail = nodes[fail].fail;
+                };
+                nodes[child].fail = target;
+                let inherited = nodes[target].outputs.clone();
+                nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        Self { nodes, lengths }
+    }
+
+    /** the number of patterns */
+    pub fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    /** every occurrence, overlapping ones included, ordered by where they end; longer matches come first at the same end */
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Match> + 'a {
+        let mut node = 0;
+        text.bytes().enumerate().flat_map(move |(i, byte)| {
+            node = self.next(node, byte);
+            self.nodes[node].outputs.iter().map(move |&pattern| Match { pattern, start: i + 1 - self.lengths[pattern], end: i + 1 })
+        })
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find_iter(text).next().is_some()
+    }
+
+    fn next(&self, mut node: usize, byte: u8) -> usize {
+        loop {
+            if let Some(child) = self.nodes[node].child(byte) {
+                return child;
+            }
+            if node == 0 {
+                return 0;
+            }
+            node = self.nodes[node].fail;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::testing::Rng;
+
+    #[test]
+    fn test_aho_corasick() {
+        let automaton = AhoCorasick::new(["he", "she", "his", "hers"]);
+        let matches: Vec<(usize, usize)> = automaton.find_iter("ushers").map(|m| (m.pattern, m.start)).collect();
+        assert_eq!(matches, vec![(1, 1), (0, 2), (3, 2)]);
+        assert!(!automaton.is_match("hi"));
+    }
+
+    #[test]
+    fn test_aho_corasick_against_naive() {
+        let mut rng = Rng::new(49);
+        let word = |rng: &mut Rng, len| (0..len).map(|_| *rng.choose(&['a', 'b', 'c'])).collect::<String>();
+        for _ in 0..100 {
+            let patterns: Vec<String> = (0..5)
+                .map(|_| {
+                    let len = rng.range(1..=4) as usize;
+                    word(&mut rng, len)
+                })
+                .collect();
+            let text = word(&mut rng, 60);
+            let automaton = AhoCorasick::new(&patterns);
+
+            let mut found: Vec<Match> = automaton.find_iter(&text).collect();
+            let mut expected: Vec<Match> = Vec::new();
+            for (pattern, p) in patterns.iter().enumerate() {
+                for start in 0..=text.len() - p.len() {
+                    if text[start..].starts_with(p.as_str()) {
+                        expected.push(Match { pattern, start, end: start + p.len() });
+                    }
+                }
+            }
+            found.sort_by_key(|m| (m.start, m.pattern));
+            expected.sort_by_key(|m| (m.start, m.pattern));
+            assert_eq!(found, expected);
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub use aho_corasick::{AhoCorasick, Match};
+pub use pattern::{Captures, Pattern, PatternError};
+pub use word_search::{word_search, word_search_in, WordMatch};
+
+mod aho_corasick;
+mod pattern;
+mod word_search;
@@ -0,0 +1,166 @@
+use std::fmt::Display;
+use std::ops::Index;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PatternError {
+    /** a `{` without its `}`, or a `}` that is not escaped as `}}` */
+    UnmatchedBrace { offset: usize },
+    /** two captures without text between them, like `{}{}`, where it is unclear where the first number ends */
+    AdjacentCaptures { offset: usize },
+}
+
+impl Display for PatternError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatternError::UnmatchedBrace { offset } => {
+                write!(f, "unmatched brace at {offset}, write `{{{{` or `}}}}` for a literal brace")
+            }
+            PatternError::AdjacentCaptures { offset } => write!(f, "capture at {offset} directly follows another capture"),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Literal(String),
+    Number,
+}
+
+/** the numbers captured by a match, and where the match is in the text as byte offsets */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Captures {
+    pub start: usize,
+    pub end: usize,
+    pub values: Vec<i64>,
+}
+
+impl Index<usize> for Captures {
+    type Output = i64;
+
+    fn index(&self, i: usize) -> &i64 {
+        &self.values[i]
+    }
+}
+
+/** a text pattern where `{}` captures an integer like `42` or `-7`, e.g. `mul({},{})`; everything else matches literally */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    tokens: Vec<Token>,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, PatternError> {
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut chars = pattern.char_indices().peekable();
+        while let Some((offset, c)) = chars.next() {
+            match (c, chars.peek().map(|(_, next)| *next)) {
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    chars.next();
+                    literal.push(c);
+                }
+                ('{', Some('}')) => {
+                    chars.next();
+                    if literal.is_empty() && tokens.last() == Some(&Token::Number) {
+                        return Err(PatternError::AdjacentCaptures { offset });
+                    }
+                    if !literal.is_empty() {
+                        tokens.push(Token::Literal(std::mem::take(&mut literal)));
+                    }
+                    tokens.push(Token::Number);
+                }
+                ('{' | '}', _) => return Err(PatternError::UnmatchedBrace { offset }),
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(literal));
+        }
+        Ok(Self { tokens })
+    }
+
+    /** the captures if the whole text matches */
+    pub fn matches(&self, text: &str) -> Option<Vec<i64>> {
+        self.match_prefix(text).filter(|captures| captures.end == text.len()).map(|captures| captures.values)
+    }
+
+    /** the captures if the text starts with a match */
+    pub fn match_prefix(&self, text: &str) -> Option<Captures> {
+        let mut values = Vec::new();
+        let mut rest = text;
+        for token in &self.tokens {
+            match token {
+                Token::Literal(literal) => rest = rest.strip_prefix(literal.as_str())?,
+                Token::Number => {
+                    let sign = usize::from(rest.starts_with('-'));
+                    let digits = rest[sign..].bytes().take_while(u8::is_ascii_digit).count();
+                    if digits == 0 {
+                        return None;
+                    }
+                    values.push(rest[..sign + digits].parse().ok()?);
+                    rest = &rest[sign + digits..];
+                }
+            }
+        }
+        Some(Captures { start: 0, end: text.len() - rest.len(), values })
+    }
+
+    /** every match that does not overlap an earlier one, e.g. every valid `mul({},{})` in corrupted memory */
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Captures> + 'a {
+        let mut start = 0;
+        std::iter::from_fn(move || {
+            while start < text.len() {
+                if let Some(captures) = self.match_prefix(&text[start..]).filter(|captures| captures.end > 0) {
+                    let found = Captures { start, end: start + captures.end, values: captures.values };
+                    start = found.end;
+                    return Some(found);
+                }
+                start += text[start..].chars().next().map_or(1, char::len_utf8);
+            }
+            None
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::AhoCorasick;
+
+    const MEMORY: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn test_pattern() {
+        let pattern = Pattern::new("Button A: X+{}, Y+{}").unwrap();
+        assert_eq!(pattern.matches("Button A: X+94, Y+-34"), Some(vec![94, -34]));
+        assert_eq!(pattern.matches("Button A: X+94, Y+34 "), None);
+        assert_eq!(Pattern::new("{{{}}}").unwrap().matches("{-0}"), Some(vec![0]));
+
+        assert_eq!(Pattern::new("a{}{}"), Err(PatternError::AdjacentCaptures { offset: 3 }));
+        assert_eq!(Pattern::new("a{b}"), Err(PatternError::UnmatchedBrace { offset: 1 }));
+        assert_eq!(Pattern::new("a}"), Err(PatternError::UnmatchedBrace { offset: 1 }));
+    }
+
+    #[test]
+    fn test_find_iter() {
+        let mul = Pattern::new("mul({},{})").unwrap();
+        let products: Vec<i64> = mul.find_iter(MEMORY).map(|captures| captures[0] * captures[1]).collect();
+        assert_eq!(products, vec![8, 25, 88, 40]);
+        assert_eq!(mul.find_iter("émul(1,2)").next().map(|captures| (captures.start, captures.end)), Some((2, 10)));
+
+        // part two: only multiply after `do()` or before the first `don't()`
+        let instructions = AhoCorasick::new(["mul(", "do()", "don't()"]);
+        let mut enabled = true;
+        let mut sum = 0;
+        for m in instructions.find_iter(MEMORY) {
+            match m.pattern {
+                0 => sum += mul.match_prefix(&MEMORY[m.start..]).filter(|_| enabled).map_or(0, |c| c[0] * c[1]),
+                1 => enabled = true,
+                _ => enabled = false,
+            }
+        }
+        assert_eq!(sum, 48);
+    }
+}
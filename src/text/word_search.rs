@@ -0,0 +1,75 @@
+use crate::grid::{GridLike, Point, DIRECTIONS_8};
+
+/** a word found in a grid, starting at `start` and continuing in `direction` */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WordMatch {
+    pub start: Point,
+    pub direction: Point,
+    pub length: usize,
+}
+
+impl WordMatch {
+    /** the positions of the letters, in the order of the word */
+    pub fn positions(&self) -> impl Iterator<Item = Point> {
+        let ((x, y), (dx, dy)) = (self.start, self.direction);
+        (0..self.length as i64).map(move |i| (x + i * dx, y + i * dy))
+    }
+
+    pub fn end(&self) -> Point {
+        let (x, y) = self.start;
+        let i = self.length as i64 - 1;
+        (x + i * self.direction.0, y + i * self.direction.1)
+    }
+}
+
+/** every occurrence of a word in all eight directions, like in a word search puzzle; palindromes are found twice */
+pub fn word_search(grid: &impl GridLike<Cell = char>, word: &str) -> Vec<WordMatch> {
+    word_search_in(grid, word, &DIRECTIONS_8)
+}
+
+/** every occurrence of a word in the given directions, ordered by start position and then by direction */
+pub fn word_search_in(grid: &impl GridLike<Cell = char>, word: &str, directions: &[Point]) -> Vec<WordMatch> {
+    let letters: Vec<char> = word.chars().collect();
+    let Some(first) = letters.first() else {
+        return Vec::new();
+    };
+    let length = letters.len();
+    grid.cells()
+        .filter(|(_, cell)| *cell == first)
+        .flat_map(|(start, _)| {
+            directions.iter().map(move |&direction| WordMatch { start, direction, length })
+        })
+        .filter(|m| m.positions().zip(&letters).all(|(p, letter)| grid.cell(p) == Some(letter)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use std::collections::HashMap;
+
+    const PUZZLE: &str = "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM\nMSAMASMSMX\nXMASAMXAMM\nXXAMMXXAMA\nSMSMSASXSS\nSAXAMASAAA\nMAMMMXMMMM\nMXMXAXMASX";
+
+    #[test]
+    fn test_word_search() {
+        let grid = Grid::new(PUZZLE).unwrap();
+        let matches = word_search(&grid, "XMAS");
+        assert_eq!(matches.len(), 18);
+        assert_eq!(matches[0], WordMatch { start: (4, 0), direction: (1, 1), length: 4 });
+        assert_eq!(matches[0].end(), (7, 3));
+        assert_eq!(matches[0].positions().collect::<Vec<_>>(), vec![(4, 0), (5, 1), (6, 2), (7, 3)]);
+    }
+
+    #[test]
+    fn test_crossed_words() {
+        // part two: two diagonal `MAS` that share their `A`
+        let grid = Grid::new(PUZZLE).unwrap();
+        let mut centers: HashMap<Point, usize> = HashMap::new();
+        for m in word_search_in(&grid, "MAS", &[(1, 1), (1, -1), (-1, 1), (-1, -1)]) {
+            *centers.entry(m.positions().nth(1).unwrap()).or_default() += 1;
+        }
+        assert_eq!(centers.values().filter(|count| **count == 2).count(), 9);
+        assert!(word_search(&grid, "").is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+use super::{Machine, Program, Status, VmError};
+
+/** machines whose output is sent to the input of other machines, e.g. amplifiers in a feedback loop */
+#[derive(Clone, Debug)]
+pub struct Cluster<P: Program> {
+    machines: Vec<Machine<P>>,
+    links: Vec<(usize, usize)>,
+    sent: Vec<usize>,
+}
+
+impl<P: Program> Cluster<P> {
+    pub fn new(machines: Vec<Machine<P>>) -> Self {
+        let sent = vec![0; machines.len()];
+        Self { machines, links: Vec::new(), sent }
+    }
+
+    /** sends the output of machine `from` to machine `to`; output of a machine without links stays in its queue */
+    pub fn link(mut self, from: usize, to: usize) -> Self {
+        assert!(from < self.machines.len() && to < self.machines.len(), "there is no machine {}", from.max(to));
+        self.links.push((from, to));
+        self
+    }
+
+    /** links every machine to the next one */
+    pub fn chain(self) -> Self {
+        (1..self.machines.len()).fold(self, |cluster, i| cluster.link(i - 1, i))
+    }
+
+    /** links every machine to the next one and the last one back to the first; panics for an empty cluster */
+    pub fn ring(self) -> Self {
+        assert!(!self.machines.is_empty(), "cannot link an empty cluster into a ring");
+        let last = self.machines.len() - 1;
+        self.chain().link(last, 0)
+    }
+
+    pub fn machines(&self) -> &[Machine<P>] {
+        &self.machines
+    }
+
+    pub fn machine(&self, i: usize) -> &Machine<P> {
+        &self.machines[i]
+    }
+
+    pub fn machine_mut(&mut self, i: usize) -> &mut Machine<P> {
+        &mut self.machines[i]
+    }
+
+    /** how many values machine `i` sent over its links */
+    pub fn sent(&self, i: usize) -> usize {
+        self.sent[i]
+    }
+
+    /** runs the machines in turn until all of them halt (`Halted`), the others all wait for input that
+    nobody sends (`Blocked`), or one of them reaches a breakpoint (`Breakpoint`, see `Machine::status`) */
+    pub fn run(&mut self) -> Result<Status, VmError> {
+        loop {
+            let mut progress = false;
+            for i in 0..self.machines.len() {
+                let steps = self.machines[i].steps();
+                let status = self.machines[i].run()?;
+                progress |= self.machines[i].steps() != steps;
+                self.route(i);
+                if status == Status::Breakpoint {
+                    return Ok(status);
+                }
+            }
+            if self.machines.iter().all(Machine::is_halted) {
+                return Ok(Status::Halted);
+            }
+            if !progress {
+                return Ok(Status::Blocked);
+            }
+        }
+    }
+
+    fn route(&mut self, from: usize) {
+        let targets: Vec<usize> = self.links.iter().filter(|(f, _)| *f == from).map(|(_, to)| *to).collect();
+        if targets.is_empty() {
+            return;
+        }
+        let output = self.machines[from].take_output();
+        self.sent[from] += output.len() * targets.len();
+        for to in targets {
+            self.machines[to].state_mut().input.extend(&output);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{Memory, Register, State, Step, Word};
+
+    const RELATIVE_BASE: Register = Register(0);
+
+    /** Intcode runs from memory, so it decodes instructions itself instead of using a `Listing` */
+    struct Intcode;
+
+    impl Intcode {
+        fn machine(program: &str, inputs: &[Word]) -> Machine<Intcode> {
+            let mut state = State::new(Memory::parse(program).unwrap());
+            state.input.extend(inputs);
+            Machine::with_state(Intcode, state)
+        }
+
+        fn address(state: &State, (mode, offset): (Word, Word)) -> Result<Word, VmError> {
+            let address = state.ip + offset;
+            match mode {
+                0 => state.memory.get(address),
+                1 => Ok(address),
+                2 => Ok(state.register(RELATIVE_BASE) + state.memory.get(address)?),
+                _ => Err(VmError::InvalidInstruction { ip: state.ip, text: format!("parameter mode {mode}") }),
+            }
+        }
+    }
+
+    impl Program for Intcode {
+        type Instruction = (Word, [(Word, Word); 3]);
+
+        fn fetch(&self, state: &State) -> Result<Option<Self::Instruction>, VmError> {
+            let value = state.memory.get(state.ip)?;
+            let modes = [(value / 100 % 10, 1), (value / 1000 % 10, 2), (value / 10000 % 10, 3)];
+            Ok(Some((value % 100, modes)))
+        }
+
+        fn execute(&mut self, (opcode, [a, b, c]): &Self::Instruction, state: &mut State) -> Result<Step, VmError> {
+            let get = |state: &State, parameter| state.memory.get(Self::address(state, parameter)?);
+            let (a, b, c) = (*a, *b, *c);
+            match opcode {
+                1 | 2 | 7 | 8 => {
+                    let (x, y) = (get(state, a)?, get(state, b)?);
+                    let value = match opcode {
+                        1 => x + y,
+                        2 => x * y,
+                        7 => Word::from(x < y),
+                        _ => Word::from(x == y),
+                    };
+                    state.memory.set(Self::address(state, c)?, value)?;
+                    state.ip += 4;
+                }
+                3 => {
+                    let Some(value) = state.read() else {
+                        return Ok(Step::Blocked);
+                    };
+                    state.memory.set(Self::address(state, a)?, value)?;
+                    state.ip += 2;
+                }
+                4 => {
+                    state.write(get(state, a)?);
+                    state.ip += 2;
+                }
+                5 | 6 => {
+                    let jump = (get(state, a)? != 0) == (*opcode == 5);
+                    state.ip = if jump { get(state, b)? } else { state.ip + 3 };
+                }
+                9 => {
+                    state.set_register(RELATIVE_BASE, state.register(RELATIVE_BASE) + get(state, a)?);
+                    state.ip += 2;
+                }
+                99 => return Ok(Step::Halt),
+                _ => return Err(VmError::InvalidInstruction { ip: state.ip, text: format!("opcode {opcode}") }),
+            }
+            Ok(Step::Continue)
+        }
+    }
+
+    #[test]
+    fn test_intcode() {
+        let quine = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        let mut machine = Intcode::machine(quine, &[]);
+        assert_eq!(machine.run(), Ok(Status::Halted));
+        assert_eq!(machine.take_output(), Memory::parse(quine).unwrap().words());
+
+        let mut machine = Intcode::machine("3,0,4,0,99", &[]);
+        assert_eq!(machine.run(), Ok(Status::Blocked));
+        machine.push_input(7);
+        assert_eq!((machine.run(), machine.take_output()), (Ok(Status::Halted), vec![7]));
+
+        let mut machine = Intcode::machine("1,0,0,0,42", &[]);
+        assert_eq!(machine.run(), Err(VmError::InvalidInstruction { ip: 4, text: "opcode 42".into() }));
+    }
+
+    #[test]
+    fn test_feedback_loop() {
+        let program = "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
+        let machines = [9, 8, 7, 6, 5].iter().map(|phase| Intcode::machine(program, &[*phase])).collect();
+        let mut amplifiers = Cluster::new(machines).ring();
+        amplifiers.machine_mut(0).push_input(0);
+
+        assert_eq!(amplifiers.run(), Ok(Status::Halted));
+        assert_eq!(amplifiers.machine(0).state().input.back(), Some(&139629729));
+        assert_eq!(amplifiers.sent(4), 5);
+    }
+
+    #[test]
+    fn test_deadlock() {
+        // two machines that each wait for the other
+        let mut cluster = Cluster::new(vec![Intcode::machine("3,0,4,0,99", &[]), Intcode::machine("3,0,4,0,99", &[])])
+            .link(0, 1)
+            .link(1, 0);
+        assert_eq!(cluster.run(), Ok(Status::Blocked));
+        cluster.machine_mut(1).push_input(3);
+        assert_eq!(cluster.run(), Ok(Status::Halted));
+        assert_eq!((cluster.sent(0), cluster.sent(1)), (1, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "empty cluster")]
+    fn test_empty_ring() {
+        let _ = Cluster::<Intcode>::new(Vec::new()).ring();
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{self, Debug, Display};
+
+use super::{Program, State, Step, VmError, Word};
+
+/** why a machine stopped running */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /** the machine can run further; only returned by `step` */
+    Running,
+    /** an instruction waits for input */
+    Blocked,
+    /** the instruction pointer reached a breakpoint; the instruction there has not run yet */
+    Breakpoint,
+    Halted,
+}
+
+/** an instruction that ran, with the number of steps before it and its address */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceEntry<I> {
+    pub step: u64,
+    pub ip: Word,
+    pub instruction: I,
+}
+
+impl<I: Debug> Display for TraceEntry<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>8} {:>6}: {:?}", self.step, self.ip, self.instruction)
+    }
+}
+
+/** runs a program, with breakpoints and an optional trace of the last instructions */
+#[derive(Clone, Debug)]
+pub struct Machine<P: Program> {
+    program: P,
+    state: State,
+    status: Status,
+    steps: u64,
+    breakpoints: HashSet<Word>,
+    trace: VecDeque<TraceEntry<P::Instruction>>,
+    trace_limit: usize,
+}
+
+impl<P: Program> Machine<P> {
+    pub fn new(program: P) -> Self {
+        Self::with_state(program, State::default())
+    }
+
+    /** starts with the given state, e.g. with the program loaded into memory or with preset registers */
+    pub fn with_state(program: P, state: State) -> Self {
+        Self {
+            program,
+            state,
+            status: Status::Running,
+            steps: 0,
+            breakpoints: HashSet::new(),
+            trace: VecDeque::new(),
+            trace_limit: 0,
+        }
+    }
+
+    pub fn program(&self) -> &P {
+        &self.program
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut State {
+        &mut self.state
+    }
+
+    /** how the machine stopped last */
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.status == Status::Halted
+    }
+
+    /** the number of instructions that ran */
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn push_input(&mut self, value: Word) {
+        self.state.input.push_back(value);
+    }
+
+    /** removes and returns everything the machine wrote so far */
+    pub fn take_output(&mut self) -> Vec<Word> {
+        self.state.output.drain(..).collect()
+    }
+
+    pub fn add_breakpoint(&mut self, ip: Word) {
+        self.breakpoints.insert(ip);
+    }
+
+    pub fn remove_breakpoint(&mut self, ip: Word) -> bool {
+        self.breakpoints.remove(&ip)
+    }
+
+    /** keeps the last `limit` instructions that ran; 0 turns tracing off */
+    pub fn trace(&mut self, limit: usize) {
+        self.trace_limit = limit;
+        while self.trace.len() > limit {
+            self.trace.pop_front();
+        }
+    }
+
+    /** the traced instructions, oldest first */
+    pub fn trace_entries(&self) -> impl Iterator<Item = &TraceEntry<P::Instruction>> {
+        self.trace.iter()
+    }
+
+    /** runs one instruction, ignoring breakpoints */
+    pub fn step(&mut self) -> Result<Status, VmError> {
+        if self.is_halted() {
+            return Ok(Status::Halted);
+        }
+        let Some(instruction) = self.program.fetch(&self.state)? else {
+            return Ok(self.set_status(Status::Halted));
+        };
+        let ip = self.state.ip;
+        let step = self.program.execute(&instruction, &mut self.state)?;
+
+        let status = match step {
+            Step::Continue => Status::Running,
+            // the instruction did not run yet
+            Step::Blocked => return Ok(self.set_status(Status::Blocked)),
+            Step::Halt => Status::Halted,
+        };
+        if self.trace_limit > 0 {
+            if self.trace.len() == self.trace_limit {
+                self.trace.pop_front();
+            }
+            self.trace.push_back(TraceEntry { step: self.steps, ip, instruction });
+        }
+        self.steps += 1;
+        Ok(self.set_status(status))
+    }
+
+    /** runs until the machine halts, waits for input or reaches a breakpoint; the breakpoint it starts at is skipped */
+    pub fn run(&mut self) -> Result<Status, VmError> {
+        let mut first = true;
+        loop {
+            if !first && self.breakpoints.contains(&self.state.ip) {
+                return Ok(self.set_status(Status::Breakpoint));
+            }
+            first = false;
+            match self.step()? {
+                Status::Running => {}
+                status => return Ok(status),
+            }
+        }
+    }
+
+    fn set_status(&mut self, status: Status) -> Status {
+        self.status = status;
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::tests::bunny;
+    use crate::vm::Register;
+
+    const MULTIPLY: &str = "cpy 3 b\ncpy 4 c\ninc a\ndec c\njnz c -2\ndec b\njnz b -5\nout a";
+
+    #[test]
+    fn test_breakpoints() {
+        let mut machine = bunny(MULTIPLY);
+        machine.add_breakpoint(5);
+        let mut products = Vec::new();
+        while machine.run() == Ok(Status::Breakpoint) {
+            products.push(machine.state().register(Register(0)));
+        }
+        assert_eq!(products, vec![4, 8, 12]);
+        assert!(machine.is_halted());
+        assert_eq!(machine.steps(), 1 + 3 * 15 + 1);
+
+        assert!(machine.remove_breakpoint(5));
+        assert_eq!(machine.step(), Ok(Status::Halted));
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut machine = bunny(MULTIPLY);
+        machine.trace(2);
+        assert_eq!(machine.step(), Ok(Status::Running));
+        assert_eq!(machine.run(), Ok(Status::Halted));
+
+        let trace: Vec<(u64, Word)> = machine.trace_entries().map(|entry| (entry.step, entry.ip)).collect();
+        assert_eq!(trace, vec![(45, 6), (46, 7)]);
+        assert_eq!(machine.trace_entries().last().unwrap().to_string(), "      46      7: Out(Register(Register(0)))");
+    }
+}
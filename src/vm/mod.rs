@@ -0,0 +1,313 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{Debug, Display};
+
+pub use cluster::Cluster;
+pub use machine::{Machine, Status, TraceEntry};
+
+mod cluster;
+mod machine;
+
+pub type Word = i64;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum VmError {
+    Parse { line: usize, text: String },
+    InvalidInstruction { ip: Word, text: String },
+    InvalidAddress(Word),
+}
+
+impl Display for VmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VmError::Parse { line, text } => write!(f, "line {line}: could not parse the instruction `{text}`"),
+            VmError::InvalidInstruction { ip, text } => write!(f, "invalid instruction at {ip}: {text}"),
+            VmError::InvalidAddress(address) => write!(f, "invalid memory address {address}"),
+        }
+    }
+}
+
+impl std::error::Error for VmError {}
+
+/** what the machine does after an instruction */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Continue,
+    /** the instruction waits for input; it must leave the state unchanged so it can run again once input arrives */
+    Blocked,
+    Halt,
+}
+
+/** an instruction set together with the program it runs */
+pub trait Program {
+    type Instruction: Clone + Debug;
+
+    /** the instruction at the instruction pointer; None halts the machine */
+    fn fetch(&self, state: &State) -> Result<Option<Self::Instruction>, VmError>;
+
+    /** runs an instruction, which moves the instruction pointer itself */
+    fn execute(&mut self, instruction: &Self::Instruction, state: &mut State) -> Result<Step, VmError>;
+}
+
+/** an instruction of a `Listing`, i.e. a language with one instruction per line such as assembunny or the ALU */
+pub trait Instruction: Sized + Clone + Debug {
+    fn parse(line: &str) -> Option<Self>;
+
+    /** runs the instruction and moves the instruction pointer; `program` is the whole listing, for instructions that modify it */
+    fn execute(&self, state: &mut State, program: &mut [Self]) -> Result<Step, VmError>;
+}
+
+/** a program of one instruction per line; the instruction pointer is the index of a line, and jumping outside of the program halts */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Listing<I> {
+    instructions: Vec<I>,
+}
+
+impl<I: Instruction> Listing<I> {
+    /** parses every non-empty line with `I::parse` */
+    pub fn parse(input: &str) -> Result<Self, VmError> {
+        let instructions = input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| I::parse(line.trim()).ok_or_else(|| VmError::Parse { line: i + 1, text: line.into() }))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { instructions })
+    }
+
+    pub fn instructions(&self) -> &[I] {
+        &self.instructions
+    }
+}
+
+impl<I> From<Vec<I>> for Listing<I> {
+    fn from(instructions: Vec<I>) -> Self {
+        Self { instructions }
+    }
+}
+
+impl<I: Instruction> Program for Listing<I> {
+    type Instruction = I;
+
+    fn fetch(&self, state: &State) -> Result<Option<I>, VmError> {
+        Ok(usize::try_from(state.ip).ok().and_then(|ip| self.instructions.get(ip)).cloned())
+    }
+
+    fn execute(&mut self, instruction: &I, state: &mut State) -> Result<Step, VmError> {
+        instruction.execute(state, &mut self.instructions)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/** writes below this address grow the memory as a vector; higher ones are stored sparsely, so a stray write to a
+huge address does not allocate everything below it */
+const DENSE_WORDS: usize = 1 << 20;
+
+/** memory that grows when written to; addresses that were never written read as zero */
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Memory {
+    words: Vec<Word>,
+    sparse: BTreeMap<usize, Word>,
+}
+
+impl Memory {
+    pub fn new(words: Vec<Word>) -> Self {
+        Self { words, sparse: BTreeMap::new() }
+    }
+
+    /** parses comma-separated words like an Intcode program */
+    pub fn parse(input: &str) -> Result<Self, VmError> {
+        let words = input
+            .trim()
+            .split(',')
+            .map(|word| word.trim().parse().map_err(|_| VmError::Parse { line: 1, text: word.into() }))
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(words))
+    }
+
+    pub fn get(&self, address: Word) -> Result<Word, VmError> {
+        let index = usize::try_from(address).map_err(|_| VmError::InvalidAddress(address))?;
+        Ok(self.words.get(index).or_else(|| self.sparse.get(&index)).copied().unwrap_or(0))
+    }
+
+    pub fn set(&mut self, address: Word, value: Word) -> Result<(), VmError> {
+        let index = usize::try_from(address).map_err(|_| VmError::InvalidAddress(address))?;
+        if index < self.words.len() {
+            self.words[index] = value;
+        } else if index < DENSE_WORDS {
+            self.words.resize(index + 1, 0);
+            self.words[index] = value;
+        } else {
+            self.sparse.insert(index, value);
+        }
+        Ok(())
+    }
+
+    /** the words up to the highest address written below 2^20; higher addresses are only available through `get` */
+    pub fn words(&self) -> &[Word] {
+        &self.words
+    }
+}
+
+/** a register, usually named by a lowercase letter: `a` is `Register(0)` */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Register(pub usize);
+
+impl Register {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.as_bytes() {
+            [c @ b'a'..=b'z'] => Some(Self((c - b'a') as usize)),
+            _ => None,
+        }
+    }
+}
+
+/** the argument of an instruction: either a register or a literal value */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Register(Register),
+    Value(Word),
+}
+
+impl Operand {
+    pub fn parse(text: &str) -> Option<Self> {
+        Register::parse(text).map(Operand::Register).or_else(|| text.parse().ok().map(Operand::Value))
+    }
+}
+
+/** the instruction pointer, registers, memory and I/O queues of a machine */
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct State {
+    pub ip: Word,
+    pub memory: Memory,
+    pub input: VecDeque<Word>,
+    pub output: VecDeque<Word>,
+    registers: Vec<Word>,
+}
+
+impl State {
+    pub fn new(memory: Memory) -> Self {
+        Self { memory, ..Self::default() }
+    }
+
+    /** the value of a register, zero if it was never set */
+    pub fn register(&self, register: Register) -> Word {
+        self.registers.get(register.0).copied().unwrap_or(0)
+    }
+
+    pub fn set_register(&mut self, register: Register, value: Word) {
+        if register.0 >= self.registers.len() {
+            self.registers.resize(register.0 + 1, 0);
+        }
+        self.registers[register.0] = value;
+    }
+
+    pub fn value(&self, operand: Operand) -> Word {
+        match operand {
+            Operand::Register(register) => self.register(register),
+            Operand::Value(value) => value,
+        }
+    }
+
+    /** takes the next input; an instruction that gets None should return `Step::Blocked` */
+    pub fn read(&mut self) -> Option<Word> {
+        self.input.pop_front()
+    }
+
+    pub fn write(&mut self, value: Word) {
+        self.output.push_back(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /** assembunny, with `tgl` to show self-modifying listings */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub(super) enum Bunny {
+        Cpy(Operand, Operand),
+        Inc(Operand),
+        Dec(Operand),
+        Jnz(Operand, Operand),
+        Tgl(Operand),
+        Out(Operand),
+    }
+
+    impl Instruction for Bunny {
+        fn parse(line: &str) -> Option<Self> {
+            let mut parts = line.split_whitespace();
+            let (name, x, y) = (parts.next()?, parts.next().and_then(Operand::parse), parts.next().and_then(Operand::parse));
+            Some(match name {
+                "cpy" => Bunny::Cpy(x?, y?),
+                "inc" => Bunny::Inc(x?),
+                "dec" => Bunny::Dec(x?),
+                "jnz" => Bunny::Jnz(x?, y?),
+                "tgl" => Bunny::Tgl(x?),
+                "out" => Bunny::Out(x?),
+                _ => return None,
+            })
+        }
+
+        fn execute(&self, state: &mut State, program: &mut [Self]) -> Result<Step, VmError> {
+            let mut next = state.ip + 1;
+            match *self {
+                // toggling can create instructions that write to a value, which are skipped
+                Bunny::Cpy(x, Operand::Register(r)) => state.set_register(r, state.value(x)),
+                Bunny::Inc(Operand::Register(r)) => state.set_register(r, state.register(r) + 1),
+                Bunny::Dec(Operand::Register(r)) => state.set_register(r, state.register(r) - 1),
+                Bunny::Jnz(x, y) if state.value(x) != 0 => next = state.ip + state.value(y),
+                Bunny::Tgl(x) => {
+                    if let Some(target) = usize::try_from(state.ip + state.value(x)).ok().and_then(|i| program.get_mut(i)) {
+                        *target = match *target {
+                            Bunny::Inc(x) => Bunny::Dec(x),
+                            Bunny::Dec(x) | Bunny::Tgl(x) | Bunny::Out(x) => Bunny::Inc(x),
+                            Bunny::Jnz(x, y) => Bunny::Cpy(x, y),
+                            Bunny::Cpy(x, y) => Bunny::Jnz(x, y),
+                        };
+                    }
+                }
+                Bunny::Out(x) => state.write(state.value(x)),
+                _ => {}
+            }
+            state.ip = next;
+            Ok(Step::Continue)
+        }
+    }
+
+    pub(super) fn bunny(input: &str) -> Machine<Listing<Bunny>> {
+        Machine::new(Listing::parse(input).unwrap())
+    }
+
+    #[test]
+    fn test_listing() {
+        let mut machine = bunny("cpy 41 a\ninc a\ninc a\ndec a\njnz a 2\ndec a\nout a");
+        assert_eq!(machine.run(), Ok(Status::Halted));
+        assert_eq!(machine.state().register(Register(0)), 42);
+        assert_eq!(machine.take_output(), vec![42]);
+
+        let mut machine = bunny("cpy 2 a\ntgl a\ntgl a\ntgl a\ncpy 1 a\ndec a\ndec a");
+        assert_eq!(machine.run(), Ok(Status::Halted));
+        assert_eq!(machine.state().register(Register(0)), 3);
+        assert_eq!(machine.program().instructions()[3], Bunny::Inc(Operand::Register(Register(0))));
+
+        assert_eq!(
+            Listing::<Bunny>::parse("inc a\n\nmul a b"),
+            Err(VmError::Parse { line: 3, text: "mul a b".into() })
+        );
+    }
+
+    #[test]
+    fn test_memory() {
+        let mut memory = Memory::parse("1,9,10,3\n").unwrap();
+        assert_eq!((memory.get(3), memory.get(100)), (Ok(3), Ok(0)));
+        assert_eq!(memory.set(6, -1), Ok(()));
+        assert_eq!(memory.words(), &[1, 9, 10, 3, 0, 0, -1]);
+        assert_eq!(memory.get(-1), Err(VmError::InvalidAddress(-1)));
+        assert_eq!(memory.set(1_000_000_000_000, 5), Ok(()));
+        assert_eq!((memory.get(1_000_000_000_000), memory.get(999_999_999_999)), (Ok(5), Ok(0)));
+        assert_eq!(memory.words().len(), 7);
+        assert_eq!(Operand::parse("-7"), Some(Operand::Value(-7)));
+        assert_eq!(Operand::parse("ab"), None);
+    }
+}